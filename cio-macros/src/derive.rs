@@ -0,0 +1,178 @@
+// derive.rs
+//! `#[derive(Row)]`, the `cio::table::Row` implementation of a struct, and
+//! `#[derive(CioDisplay)]`: the `cio::render::CioDisplay`, `Debug` and
+//! `cio::table::Row` implementations of a struct, following the
+//! `#[cio(skip)]`, `#[cio(rename = "...")]` and `#[cio(spec = "...")]`
+//! attributes of its fields.
+
+use crate::template::Template;
+use crate::{expand_format, Arguments};
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::ext::IdentExt;
+use syn::{parse_quote, Data, DeriveInput, Fields, Generics, LitStr, Member};
+
+/// `generics` with a `Debug` bound on each type parameter, as
+/// `#[derive(Debug)]` adds: fields without `Display` are shown with `Debug`.
+fn debug_bounds(generics: &Generics) -> Generics {
+    let mut bounded = generics.clone();
+    let params: Vec<syn::Ident> = generics.type_params().map(|param| param.ident.clone()).collect();
+    let where_clause = bounded.make_where_clause();
+    for param in params {
+        where_clause.predicates.push(parse_quote!(#param: ::std::fmt::Debug));
+    }
+    bounded
+}
+
+pub(crate) fn expand_row(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new_spanned(&input.ident, "`Row` can only be derived for structs"));
+    };
+    let name = &input.ident;
+    let generics = debug_bounds(&input.generics);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let members: Vec<Member> = data.fields.members().collect();
+    let headers = match &data.fields {
+        Fields::Named(fields) => {
+            let names = fields.named.iter().filter_map(|field| field.ident.as_ref()).map(|ident| ident.unraw().to_string());
+            quote!(::std::option::Option::Some(::std::vec![#(#names.to_string()),*]))
+        }
+        _ => quote!(::std::option::Option::None),
+    };
+    Ok(quote! {
+        impl #impl_generics ::cio::table::Row for #name #ty_generics #where_clause {
+            fn headers() -> ::std::option::Option<::std::vec::Vec<::std::string::String>> {
+                #headers
+            }
+
+            fn cells(&self) -> ::std::vec::Vec<::std::string::String> {
+                #[allow(unused_imports)]
+                use ::cio::table::{DebugCell as _, DisplayCell as _};
+                ::std::vec![#((&::cio::table::Cell(&self.#members)).cell()),*]
+            }
+        }
+    })
+}
+
+/// A field shown by the derived implementations.
+struct Shown {
+    name: String,
+    ident: syn::Ident,
+    /// The `format!` call of its `spec`, if any.
+    formatted: Option<TokenStream2>,
+}
+
+pub(crate) fn expand_cio_display(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => return Err(syn::Error::new_spanned(&input.ident, "`CioDisplay` can only be derived for structs with named fields")),
+        },
+        _ => return Err(syn::Error::new_spanned(&input.ident, "`CioDisplay` can only be derived for structs with named fields")),
+    };
+    let mut shown = Vec::new();
+    let mut errors: Option<syn::Error> = None;
+    for field in fields {
+        let ident = field.ident.clone().expect("named field");
+        match parse_field(ident, &field.attrs) {
+            Ok(Some(field)) => shown.push(field),
+            Ok(None) => {}
+            Err(e) => match &mut errors {
+                Some(errors) => errors.combine(e),
+                None => errors = Some(e),
+            },
+        }
+    }
+    if let Some(errors) = errors {
+        return Err(errors);
+    }
+
+    let name = &input.ident;
+    let name_str = name.unraw().to_string();
+    let generics = debug_bounds(&input.generics);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let names: Vec<&String> = shown.iter().map(|field| &field.name).collect();
+    let values = shown.iter().map(|field| {
+        let ident = &field.ident;
+        match &field.formatted {
+            Some(formatted) => quote!(::cio::render::Value::Text(#formatted)),
+            None => quote!(::cio::render::Value::Debug(&self.#ident)),
+        }
+    });
+    let cells = shown.iter().map(|field| {
+        let ident = &field.ident;
+        match &field.formatted {
+            Some(formatted) => quote!(#formatted),
+            None => quote!((&::cio::table::Cell(&self.#ident)).cell()),
+        }
+    });
+    Ok(quote! {
+        impl #impl_generics ::cio::render::CioDisplay for #name #ty_generics #where_clause {
+            const NAME: &'static str = #name_str;
+
+            fn fields(&self) -> ::std::vec::Vec<(&'static str, ::cio::render::Value<'_>)> {
+                ::std::vec![#((#names, #values)),*]
+            }
+        }
+
+        impl #impl_generics ::std::fmt::Debug for #name #ty_generics #where_clause {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                ::cio::render::debug_fields(self, f)
+            }
+        }
+
+        impl #impl_generics ::cio::table::Row for #name #ty_generics #where_clause {
+            fn headers() -> ::std::option::Option<::std::vec::Vec<::std::string::String>> {
+                ::std::option::Option::Some(::std::vec![#(#names.to_string()),*])
+            }
+
+            fn cells(&self) -> ::std::vec::Vec<::std::string::String> {
+                #[allow(unused_imports)]
+                use ::cio::table::{DebugCell as _, DisplayCell as _};
+                ::std::vec![#(#cells),*]
+            }
+        }
+    })
+}
+
+/// Reads the `#[cio(...)]` attributes of a field, returning `None` if it is
+/// skipped.
+fn parse_field(ident: syn::Ident, attrs: &[syn::Attribute]) -> syn::Result<Option<Shown>> {
+    let mut skip = false;
+    let mut rename: Option<LitStr> = None;
+    let mut spec: Option<LitStr> = None;
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("cio")) {
+        attr.parse_nested_meta(|meta| {
+            let slot = if meta.path.is_ident("skip") {
+                skip = true;
+                return Ok(());
+            } else if meta.path.is_ident("rename") {
+                &mut rename
+            } else if meta.path.is_ident("spec") {
+                &mut spec
+            } else {
+                return Err(meta.error("unknown `cio` attribute; expected `skip`, `rename` or `spec`"));
+            };
+            if slot.is_some() {
+                return Err(meta.error("duplicate `cio` attribute"));
+            }
+            *slot = Some(meta.value()?.parse()?);
+            Ok(())
+        })?;
+    }
+    if skip {
+        return Ok(None);
+    }
+    let name = rename.map_or_else(|| ident.unraw().to_string(), |rename| rename.value());
+    let formatted = match spec {
+        // The spec goes through the placeholder machinery, as in
+        // `sprintf!("{self.field:spec}")`, with errors pointing at it.
+        Some(spec) => {
+            let placeholder = Template::generated(format!("{{self.{}:{}}}", ident, spec.value()), spec.span());
+            let (fmt, args) = expand_format(&placeholder, &Arguments::default(), quote!(Stdout))?;
+            Some(quote!(::std::format!(#fmt, #(#args),*)))
+        }
+        None => None,
+    };
+    Ok(Some(Shown { name, ident, formatted }))
+}
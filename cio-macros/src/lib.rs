@@ -0,0 +1,658 @@
+// lib.rs
+mod derive;
+mod prompt;
+mod scan;
+mod spec;
+mod style;
+mod template;
+
+use proc_macro::TokenStream;
+use proc_macro2::{Group, Ident, Span, TokenStream as TokenStream2, TokenTree};
+use quote::quote;
+use scan::{Item, Placeholder};
+use spec::{Conversion, Spec, FIELD};
+use style::Markup;
+use std::ops::Range;
+use syn::parse::{Parse, ParseStream};
+use template::Template;
+use syn::punctuated::Punctuated;
+use syn::{parse_macro_input, parse_quote_spanned, DeriveInput, Expr, ExprLit, Lit, LitStr, Token};
+
+/// A format string being expanded, together with the errors found so far.
+struct FormatString<'a> {
+    template: &'a Template,
+    value: String,
+    arguments: &'a Arguments,
+    /// Whether each positional, then named, argument is referenced.
+    used: Vec<bool>,
+    /// The index of the positional argument of the next `{}`.
+    next: usize,
+    /// The `[style]` tags open so far, with their SGR parameters.
+    styles: Vec<(String, String)>,
+    /// The `cio::style::Stream` deciding whether escapes are printed.
+    stream: TokenStream2,
+    /// The placeholder being expanded, which errors quote.
+    placeholder: Option<Range<usize>>,
+    errors: Vec<syn::Error>,
+}
+
+impl<'a> FormatString<'a> {
+    fn new(template: &'a Template, arguments: &'a Arguments, stream: TokenStream2) -> Self {
+        let used = vec![false; arguments.positional.len() + arguments.named.len()];
+        let value = template.value.clone();
+        FormatString {
+            template,
+            value,
+            arguments,
+            used,
+            next: 0,
+            styles: Vec::new(),
+            stream,
+            placeholder: None,
+            errors: Vec::new(),
+        }
+    }
+
+    /// Pushes the literal text at `range`, turning its `[style]` and `[/]`
+    /// tags into escape sequences.
+    fn push_text(&mut self, range: Range<usize>, fmt: &mut String, args: &mut Vec<TokenStream2>) {
+        let text = self.value[range.clone()].to_string();
+        for item in style::markup(&text) {
+            match item {
+                Markup::Text(text_range) => fmt.push_str(&text[text_range]),
+                Markup::Open { tag, sgr, .. } => {
+                    self.push_escape(style::sequence(&sgr), fmt, args);
+                    self.styles.push((tag, sgr));
+                }
+                Markup::Close { range: tag_range, tag } => {
+                    let tag_range = range.start + tag_range.start..range.start + tag_range.end;
+                    let Some((open, sgr)) = self.styles.pop() else {
+                        self.error(tag_range, "closing tag without a matching `[style]` tag");
+                        continue;
+                    };
+                    if let Some(tag) = tag.filter(|tag| style::parse_style(tag).as_ref() != Some(&sgr)) {
+                        self.error(tag_range, format!("closing tag `[/{}]` does not match the open tag `[{}]`", tag, open));
+                    }
+                    self.push_escape(style::reset(&self.styles), fmt, args);
+                }
+            }
+        }
+    }
+
+    /// Pushes an escape sequence, printed only when colors are enabled.
+    fn push_escape(&self, escape: String, fmt: &mut String, args: &mut Vec<TokenStream2>) {
+        let stream = &self.stream;
+        fmt.push_str("{}");
+        args.push(quote!(::cio::style::escape(#escape, ::cio::style::Stream::#stream)));
+    }
+
+    /// Span of `range` (byte offsets in the unescaped value) inside the
+    /// format string. Falls back to the whole token where the compiler cannot
+    /// subspan.
+    fn span(&self, range: Range<usize>) -> Span {
+        self.template.span(range)
+    }
+
+    /// Reports `message` at `range`, quoting the placeholder being expanded
+    /// and giving its position, since stable compilers cannot point inside
+    /// the string.
+    fn error(&mut self, range: Range<usize>, message: impl std::fmt::Display) {
+        let start = self.placeholder.as_ref().map_or(range.start, |placeholder| placeholder.start);
+        let at = match self.template.location(start) {
+            Some(location) => format!(" at {}", location),
+            None => String::new(),
+        };
+        let message = match &self.placeholder {
+            Some(placeholder) => format!("{} (in `{}`{})", message, &self.value[placeholder.clone()], at),
+            None if at.is_empty() => message.to_string(),
+            None => format!("{} ({})", message, at.trim_start()),
+        };
+        self.errors.push(syn::Error::new(self.span(range), message));
+    }
+
+    /// Parses the spec found at `range`, evaluating its nested replacement
+    /// fields like `{w}` in `{x:>{w}}`, whose expressions are at `fields`.
+    fn parse_spec(&mut self, range: Range<usize>, fields: &[Range<usize>]) -> Option<Spec> {
+        let spec = self.value[range.clone()].to_string();
+        if fields.is_empty() {
+            let parsed = Spec::parse(&spec);
+            if parsed.is_none() {
+                let hint = match spec.contains(['*', '$']) {
+                    true => "; give a dynamic width or precision as `{name}`, like `{x:>{w}.{p}}`",
+                    false => "",
+                };
+                self.error(range, format!("unknown format spec `{}`{}", spec, hint));
+            }
+            return parsed;
+        }
+        let mut marked = String::new();
+        let mut exprs = Vec::new();
+        let mut valid = true;
+        let mut last = range.start;
+        for field in fields {
+            marked.push_str(&self.value[last..field.start - 1]);
+            marked.push(FIELD);
+            match self.resolve(field.clone()) {
+                Some(expr) => exprs.push(expr),
+                None => valid = false,
+            }
+            last = field.end + 1;
+        }
+        marked.push_str(&self.value[last..range.end]);
+        if !valid {
+            return None;
+        }
+        Spec::parse_nested(&marked, exprs).map_err(|e| self.error(range, e)).ok()
+    }
+
+    /// Resolves the text at `range` to the argument it refers to, as
+    /// `format_args!` does: the next positional argument if empty, the
+    /// positional argument of that index if an integer, or the named argument
+    /// of that name. Anything else is an inline expression, and so are
+    /// integers when there are no positional arguments.
+    fn resolve(&mut self, range: Range<usize>) -> Option<Expr> {
+        let text = self.value[range.clone()].trim();
+        let positional = self.arguments.positional.len();
+        let index = if text.is_empty() {
+            if positional == 0 {
+                let message = "empty placeholder `{}`; write the expression between the braces or pass an argument";
+                self.error(range, message);
+                return None;
+            }
+            self.next += 1;
+            self.next - 1
+        } else if let (Ok(index), 1..) = (text.parse::<usize>(), positional) {
+            index
+        } else if let Some(named) = self.arguments.named.iter().position(|(name, _)| name == text) {
+            self.used[positional + named] = true;
+            return Some(Arguments::binding(positional + named, self.span(range)));
+        } else {
+            return self.parse_expr(range);
+        };
+        if index >= positional {
+            let given = match positional {
+                0 => "no arguments were given".to_string(),
+                1 => "there is 1 argument".to_string(),
+                n => format!("there are {} arguments", n),
+            };
+            self.error(range, format!("invalid reference to positional argument {} ({})", index, given));
+            return None;
+        }
+        self.used[index] = true;
+        Some(Arguments::binding(index, self.span(range)))
+    }
+
+    /// Parses the expression found at `range`, spanned inside the literal.
+    fn parse_expr(&mut self, range: Range<usize>) -> Option<Expr> {
+        let text = self.value[range.clone()].trim();
+        let span = self.span(range.clone());
+        let parsed = text
+            .parse::<TokenStream2>()
+            .map_err(|e| e.to_string())
+            .and_then(|tokens| syn::parse2::<Expr>(respan(tokens, span)).map_err(|e| e.to_string()))
+            .map_err(|e| format!("invalid expression `{}`: {}", text, e));
+        match parsed {
+            Ok(expr) => Some(expr),
+            Err(e) => {
+                self.error(range, e);
+                None
+            }
+        }
+    }
+
+    /// Reports the arguments that no placeholder refers to.
+    fn check_unused(&mut self) {
+        let arguments = self.arguments.positional.iter().map(|expr| (expr, "argument never used"));
+        let named = self.arguments.named.iter().map(|(_, expr)| (expr, "named argument never used"));
+        for ((expr, message), used) in arguments.chain(named).zip(&self.used) {
+            if !used {
+                self.errors.push(syn::Error::new_spanned(expr, message));
+            }
+        }
+    }
+
+    fn into_result<T>(mut self, value: T) -> syn::Result<T> {
+        self.check_unused();
+        let mut errors = self.errors.into_iter();
+        match errors.next() {
+            None => Ok(value),
+            Some(mut first) => {
+                first.extend(errors);
+                Err(first)
+            }
+        }
+    }
+}
+
+/// Gives every token of `tokens` the span `span`, so that errors in the
+/// generated code point at the placeholder rather than the whole invocation.
+fn respan(tokens: TokenStream2, span: Span) -> TokenStream2 {
+    tokens
+        .into_iter()
+        .map(|mut tree| {
+            if let TokenTree::Group(group) = &tree {
+                let mut inner = Group::new(group.delimiter(), respan(group.stream(), span));
+                inner.set_span(span);
+                tree = TokenTree::Group(inner);
+            } else {
+                tree.set_span(span);
+            }
+            tree
+        })
+        .collect()
+}
+
+/// Splits the format string into a `std::fmt` format string and the
+/// arguments computed from its placeholders, collecting every error.
+fn expand_format(template: &Template, arguments: &Arguments, stream: TokenStream2) -> syn::Result<(String, Vec<TokenStream2>)> {
+    let mut fs = FormatString::new(template, arguments, stream);
+    let fmt_str = fs.value.clone();
+    let mut args = Vec::new();
+    let mut final_fmt = String::with_capacity(fmt_str.len());
+    let mut last = 0;
+    for item in scan::scan(&fmt_str) {
+        fs.placeholder = None;
+        let Placeholder { whole, expr: mut expr_range, spec: mut spec_range, fields } = match item {
+            Item::Placeholder(placeholder) => placeholder,
+            Item::UnmatchedOpen(i) => {
+                fs.placeholder = None;
+                fs.error(i..i + 1, "unmatched `{` in format string; use `{{` for a literal brace");
+                continue;
+            }
+            Item::UnmatchedClose(i) => {
+                fs.placeholder = None;
+                fs.error(i..i + 1, "unmatched `}` in format string; use `}}` for a literal brace");
+                continue;
+            }
+        };
+        fs.push_text(last..whole.start, &mut final_fmt, &mut args);
+        fs.placeholder = Some(whole.clone());
+        last = whole.end;
+        // `{x:green}` and `{x:>8 bold}` style the value formatted by the
+        // rest of the spec.
+        let style = spec_range.clone().and_then(|range| {
+            let (len, sgr) = style::split_spec(&fmt_str[range.clone()])?;
+            spec_range = (len > 0).then_some(range.start..range.start + len);
+            Some(sgr)
+        });
+        let conversion = Conversion::split(&fmt_str[expr_range.clone()]).map(|(len, conversion)| {
+            expr_range.end = expr_range.start + len;
+            conversion
+        });
+        // `{expr=}` and `{expr = :spec}` print the expression text, with its
+        // whitespace, before the value, which defaults to `Debug` as in Python.
+        let self_documenting = self_documenting_len(&fmt_str[expr_range.clone()]);
+        if let Some(len) = self_documenting {
+            final_fmt.push_str(&escape_braces(&fmt_str[expr_range.clone()]));
+            expr_range.end = expr_range.start + len;
+        }
+        // Resolved before the spec, whose `{}` fields take the positional
+        // arguments after the value's, as in Python.
+        let expr = fs.resolve(expr_range);
+        let spec = match &spec_range {
+            None if self_documenting.is_some() && conversion.is_none() => Some(Spec::Compact { sorted: false }),
+            None => Some(Spec::Display),
+            Some(range) => match fs.parse_spec(range.clone(), &fields) {
+                Some(parsed) if conversion.is_some() && !parsed.applies_to_text() => {
+                    let spec = &fmt_str[range.clone()];
+                    let message = format!("format spec `{}` cannot follow a conversion flag, which produces a string", spec);
+                    fs.error(range.clone(), message);
+                    None
+                }
+                parsed => parsed,
+            },
+        };
+        let Some(mut expr) = expr else {
+            continue;
+        };
+        if let Some(conversion) = conversion {
+            expr = conversion.apply(&expr);
+        }
+        let Some(spec) = spec else {
+            continue;
+        };
+        if let Some(sgr) = &style {
+            fs.push_escape(style::sequence(sgr), &mut final_fmt, &mut args);
+        }
+        let (placeholder, arg) = spec.expand(&expr);
+        final_fmt.push_str(placeholder);
+        args.push(arg);
+        if style.is_some() {
+            fs.push_escape(style::reset(&fs.styles), &mut final_fmt, &mut args);
+        }
+    }
+    fs.placeholder = None;
+    fs.push_text(last..fmt_str.len(), &mut final_fmt, &mut args);
+    // Tags left open end with the format string.
+    if !fs.styles.is_empty() {
+        fs.push_escape(style::reset(&[]), &mut final_fmt, &mut args);
+    }
+    fs.into_result((final_fmt, args))
+}
+
+/// Prints the format string with its placeholders filled in, followed by a
+/// newline. The format string is a string literal, or a `concat!` or
+/// `include_str!` of them, which the macro evaluates itself.
+#[proc_macro]
+pub fn printf(input: TokenStream) -> TokenStream {
+    let FormatArgs { template, arguments } = parse_macro_input!(input as FormatArgs);
+    expand_call(&template, &arguments, quote!(println), quote!(Stdout), None).into()
+}
+
+/// Like `printf!`, but the format string is the content of the template file
+/// at the given path, relative to the directory of the crate's `Cargo.toml`.
+#[proc_macro]
+pub fn printf_file(input: TokenStream) -> TokenStream {
+    let parser = |input: ParseStream| Ok((input.parse::<LitStr>()?, input.parse::<Arguments>()?));
+    let (path, arguments) = parse_macro_input!(input with parser);
+    match Template::file(&path) {
+        Ok(template) => expand_call(&template, &arguments, quote!(println), quote!(Stdout), None).into(),
+        Err(e) => compile_errors(e).into(),
+    }
+}
+
+/// Like `printf!`, but returns the formatted `String` instead of printing it.
+#[proc_macro]
+pub fn sprintf(input: TokenStream) -> TokenStream {
+    let FormatArgs { template, arguments } = parse_macro_input!(input as FormatArgs);
+    // The string is most often printed to stdout, so its colors follow it.
+    expand_call(&template, &arguments, quote!(format), quote!(Stdout), None).into()
+}
+
+/// Short alias of `sprintf!`, in the spirit of Python's f-strings.
+#[proc_macro]
+pub fn f(input: TokenStream) -> TokenStream {
+    sprintf(input)
+}
+
+/// Like `printf!`, but prints to the standard error.
+#[proc_macro]
+pub fn eprintf(input: TokenStream) -> TokenStream {
+    let FormatArgs { template, arguments } = parse_macro_input!(input as FormatArgs);
+    expand_call(&template, &arguments, quote!(eprintln), quote!(Stderr), None).into()
+}
+
+/// Like `printf!`, but writes the line to `dest`, any `std::io::Write` or
+/// `std::fmt::Write`, and returns the `io::Result<()>`/`fmt::Result`.
+#[proc_macro]
+pub fn writef(input: TokenStream) -> TokenStream {
+    let WriteArgs { dest, fmt } = parse_macro_input!(input as WriteArgs);
+    // `dest` is not known to be a terminal.
+    expand_call(&fmt.template, &fmt.arguments, quote!(writeln), quote!(Writer), Some(&dest)).into()
+}
+
+/// Arguments of `writef!`: the destination, then the format string and its
+/// arguments.
+struct WriteArgs {
+    dest: Expr,
+    fmt: FormatArgs,
+}
+
+impl Parse for WriteArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let dest = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let fmt = input.parse()?;
+        Ok(WriteArgs { dest, fmt })
+    }
+}
+
+/// A format string followed by its arguments, as in `format_args!`.
+struct FormatArgs {
+    template: Template,
+    arguments: Arguments,
+}
+
+impl Parse for FormatArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        Ok(FormatArgs { template: input.parse()?, arguments: input.parse()? })
+    }
+}
+
+/// The arguments after a format string: positional ones, then `name = expr`
+/// ones.
+#[derive(Default)]
+struct Arguments {
+    positional: Vec<Expr>,
+    named: Vec<(String, Expr)>,
+}
+
+impl Arguments {
+    /// The variable the argument of `index`, counting the positional ones
+    /// then the named ones, is bound to, spanned at the placeholder.
+    fn binding(index: usize, span: Span) -> Expr {
+        let ident = Ident::new(&format!("__cio_arg{}", index), Span::mixed_site().located_at(span));
+        parse_quote_spanned!(span=> (*#ident))
+    }
+
+    /// Wraps `body` so that every argument is evaluated once, before it, and
+    /// borrowed by its binding.
+    fn bind(&self, body: TokenStream2) -> TokenStream2 {
+        if self.positional.is_empty() && self.named.is_empty() {
+            return body;
+        }
+        let exprs = self.positional.iter().chain(self.named.iter().map(|(_, expr)| expr));
+        let idents = (0..self.positional.len() + self.named.len())
+            .map(|index| Ident::new(&format!("__cio_arg{}", index), Span::mixed_site()));
+        quote! {
+            match (#(&(#exprs),)*) {
+                (#(#idents,)*) => #body,
+            }
+        }
+    }
+}
+
+impl Parse for Arguments {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut arguments = Arguments::default();
+        if input.is_empty() {
+            return Ok(arguments);
+        }
+        input.parse::<Token![,]>()?;
+        for arg in Punctuated::<Expr, Token![,]>::parse_terminated(input)? {
+            let name = match &arg {
+                Expr::Assign(assign) => match &*assign.left {
+                    Expr::Path(path) => path.path.get_ident().map(ToString::to_string),
+                    _ => None,
+                },
+                _ => None,
+            };
+            let Some(name) = name else {
+                if let Some((name, _)) = arguments.named.last() {
+                    let message = format!("positional arguments cannot follow named arguments such as `{}`", name);
+                    return Err(syn::Error::new_spanned(arg, message));
+                }
+                arguments.positional.push(arg);
+                continue;
+            };
+            let Expr::Assign(assign) = arg else { unreachable!() };
+            if arguments.named.iter().any(|(other, _)| *other == name) {
+                return Err(syn::Error::new_spanned(&assign.left, format!("duplicate argument named `{}`", name)));
+            }
+            arguments.named.push((name, *assign.right));
+        }
+        Ok(arguments)
+    }
+}
+
+/// Expands `template` and its `arguments` into a call to the `std`
+/// formatting macro `target`, with `dest` as its first argument if any.
+/// `stream` names the `cio::style::Stream` whose color choice applies.
+fn expand_call(template: &Template, arguments: &Arguments, target: TokenStream2, stream: TokenStream2, dest: Option<&Expr>) -> TokenStream2 {
+    let (final_fmt, args) = match expand_format(template, arguments, stream) {
+        Ok(expanded) => expanded,
+        Err(e) => return compile_errors(e),
+    };
+    let dest = dest.into_iter();
+    let call = arguments.bind(quote!(#target!(#(#dest,)* #final_fmt, #(#args),*)));
+    let dependencies = template.dependencies();
+    if dependencies.is_empty() {
+        return call;
+    }
+    quote!({
+        #dependencies
+        #call
+    })
+}
+
+/// Like `printf!`, but mirrors Python's `print()`: any number of values,
+/// where string literals get placeholder expansion, then optional `sep`,
+/// `end` and `flush` keyword arguments. `print_py!("...", end = "")` prints
+/// without the trailing newline.
+#[proc_macro]
+pub fn print_py(input: TokenStream) -> TokenStream {
+    let PrintArgs { values, sep, end, flush } = parse_macro_input!(input as PrintArgs);
+    let sep = sep.map_or(" ".to_string(), |sep| escape_braces(&sep.value()));
+    let end = end.map_or("\n".to_string(), |end| escape_braces(&end.value()));
+    let mut final_fmt = String::new();
+    let mut args = Vec::new();
+    let mut errors: Option<syn::Error> = None;
+    for (i, value) in values.iter().enumerate() {
+        if i > 0 {
+            final_fmt.push_str(&sep);
+        }
+        match value {
+            Expr::Lit(ExprLit { lit: Lit::Str(lit), .. }) => match expand_format(&Template::literal(lit), &Arguments::default(), quote!(Stdout)) {
+                Ok((fmt, lit_args)) => {
+                    final_fmt.push_str(&fmt);
+                    args.extend(lit_args);
+                }
+                Err(e) => match &mut errors {
+                    Some(errors) => errors.combine(e),
+                    None => errors = Some(e),
+                },
+            },
+            value => {
+                final_fmt.push_str("{}");
+                args.push(quote!(#value));
+            }
+        }
+    }
+    if let Some(errors) = errors {
+        return compile_errors(errors).into();
+    }
+    final_fmt.push_str(&end);
+    let flush = flush.map(|flush| {
+        quote! {
+            if #flush {
+                std::io::Write::flush(&mut std::io::stdout()).expect("Failed to flush stdout");
+            }
+        }
+    });
+    quote! {{
+        print!(#final_fmt, #(#args),*);
+        #flush
+    }}
+    .into()
+}
+
+/// Arguments of `print_py!`: the values to print, then the keyword arguments.
+struct PrintArgs {
+    values: Vec<Expr>,
+    sep: Option<LitStr>,
+    end: Option<LitStr>,
+    flush: Option<Expr>,
+}
+
+impl Parse for PrintArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut values = Vec::new();
+        let (mut sep, mut end, mut flush) = (None, None, None);
+        for arg in Punctuated::<Expr, Token![,]>::parse_terminated(input)? {
+            let Expr::Assign(assign) = arg else {
+                if sep.is_some() || end.is_some() || flush.is_some() {
+                    return Err(syn::Error::new_spanned(arg, "positional argument follows keyword argument"));
+                }
+                values.push(arg);
+                continue;
+            };
+            let keyword = match &*assign.left {
+                Expr::Path(path) => path.path.get_ident().map(ToString::to_string),
+                _ => None,
+            };
+            let slot = match keyword.as_deref() {
+                Some("sep") => &mut sep,
+                Some("end") => &mut end,
+                Some("flush") => &mut flush,
+                _ => {
+                    let message = "invalid keyword argument; expected `sep`, `end` or `flush`";
+                    return Err(syn::Error::new_spanned(&assign.left, message));
+                }
+            };
+            if slot.is_some() {
+                let message = format!("keyword argument repeated: {}", keyword.unwrap_or_default());
+                return Err(syn::Error::new_spanned(&assign.left, message));
+            }
+            *slot = Some(*assign.right);
+        }
+        Ok(PrintArgs {
+            values,
+            sep: sep.map(string_literal).transpose()?,
+            end: end.map(string_literal).transpose()?,
+            flush,
+        })
+    }
+}
+
+fn string_literal(expr: Expr) -> syn::Result<LitStr> {
+    match expr {
+        Expr::Lit(ExprLit { lit: Lit::Str(lit), .. }) => Ok(lit),
+        expr => Err(syn::Error::new_spanned(expr, "expected a string literal")),
+    }
+}
+
+/// Returns the length of the expression of a self-documenting placeholder
+/// like `{x=}` or `{x = }`, the `=` being neither part of `==`, `!=`, `<=`
+/// nor `>=`.
+fn self_documenting_len(text: &str) -> Option<usize> {
+    let expr = text.trim_end().strip_suffix('=')?;
+    (!expr.ends_with(['=', '!', '<', '>'])).then_some(expr.len())
+}
+
+/// Escapes `{` and `}` so that `text` is printed verbatim by `std::fmt`.
+fn escape_braces(text: &str) -> String {
+    text.replace('{', "{{").replace('}', "}}")
+}
+
+/// Turns the collected errors into an expression that fails to compile.
+fn compile_errors(errors: syn::Error) -> TokenStream2 {
+    let errors = errors.to_compile_error();
+    quote!({ #errors })
+}
+
+/// Reads a value of the inferred type from a line of standard input,
+/// prompting again until it parses. Exits the program with an error message
+/// if standard input is closed or cannot be read.
+#[proc_macro]
+pub fn input(input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(input as prompt::InputArgs);
+    prompt::expand_input(args).unwrap_or_else(compile_errors).into()
+}
+
+/// Like `input!`, but returns a `Result<T, cio::InputError<T::Err>>`: `Eof`
+/// when standard input is closed, `Io` when it cannot be read, and `Empty` or
+/// `Parse` with the `FromStr` error when the last line is still invalid after
+/// `retries = n` retries (3 by default; 0 fails on the first invalid line).
+#[proc_macro]
+pub fn try_input(input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(input as prompt::InputArgs);
+    prompt::expand_try_input(args).unwrap_or_else(compile_errors).into()
+}
+
+/// Implements `cio::table::Row` for a struct, for the `:t` and `:tm` table
+/// specs: named fields become the columns, under their names, and each cell
+/// shows the field with `Display`, or `Debug` if it has no `Display`.
+#[proc_macro_derive(Row)]
+pub fn derive_row(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    derive::expand_row(input).unwrap_or_else(syn::Error::into_compile_error).into()
+}
+
+/// Implements `cio::render::CioDisplay`, `Debug` and `cio::table::Row` for a
+/// struct with named fields, honoring the `#[cio(skip)]`,
+/// `#[cio(rename = "...")]` and `#[cio(spec = "...")]` field attributes.
+#[proc_macro_derive(CioDisplay, attributes(cio))]
+pub fn derive_cio_display(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    derive::expand_cio_display(input).unwrap_or_else(syn::Error::into_compile_error).into()
+}
\ No newline at end of file
@@ -0,0 +1,259 @@
+// prompt.rs
+//! Arguments and expansion of the `input!` and `try_input!` macros.
+
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote;
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::{Expr, ExprLit, Ident, Lit, LitStr, Token};
+
+/// The prompt, then `name = value` options.
+pub(crate) struct InputArgs {
+    prompt: LitStr,
+    retries: Option<Expr>,
+    /// The value of an empty line.
+    default: Option<Expr>,
+    /// Whether an empty line is `None`, the others being `Some`.
+    allow_empty: bool,
+    /// The separator of the values of tuples and vectors, whitespace by
+    /// default.
+    sep: Option<Expr>,
+    /// A predicate on a reference to the value.
+    validate: Option<Expr>,
+    /// The error shown when a check fails, instead of the default one.
+    message: Option<Expr>,
+    /// The range containing the value.
+    range: Option<Expr>,
+    /// The regular expression the line matches, checked at compile time.
+    matches: Option<LitStr>,
+    /// The allowed values.
+    one_of: Option<Expr>,
+}
+
+impl Parse for InputArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let prompt = input.parse()?;
+        let mut args = InputArgs {
+            prompt,
+            retries: None,
+            default: None,
+            allow_empty: false,
+            sep: None,
+            validate: None,
+            message: None,
+            range: None,
+            matches: None,
+            one_of: None,
+        };
+        if input.is_empty() {
+            return Ok(args);
+        }
+        input.parse::<Token![,]>()?;
+        let mut allow_empty = None;
+        let mut matches = None;
+        for option in Punctuated::<InputOption, Token![,]>::parse_terminated(input)? {
+            let name = &option.name;
+            let slot = match name.to_string().as_str() {
+                "retries" => &mut args.retries,
+                "default" => &mut args.default,
+                "allow_empty" => &mut allow_empty,
+                "sep" => &mut args.sep,
+                "validate" => &mut args.validate,
+                "message" => &mut args.message,
+                "range" => &mut args.range,
+                "matches" => &mut matches,
+                "one_of" => &mut args.one_of,
+                _ => {
+                    let message = "unknown option; expected `retries`, `default`, `allow_empty`, `sep`, \
+                                   `validate`, `message`, `range`, `matches` or `one_of`";
+                    return Err(syn::Error::new_spanned(name, message));
+                }
+            };
+            if slot.is_some() {
+                return Err(syn::Error::new_spanned(name, format!("option repeated: {}", name)));
+            }
+            *slot = match option.value {
+                Some(value) => Some(value),
+                None if name == "allow_empty" => Some(syn::parse_quote!(true)),
+                None => return Err(syn::Error::new_spanned(name, format!("expected a value: `{} = ...`", name))),
+            };
+        }
+        if let Some(value) = allow_empty {
+            // The flag decides the type of the value, so it must be known.
+            args.allow_empty = match value {
+                Expr::Lit(ExprLit { lit: Lit::Bool(flag), .. }) => flag.value,
+                value => return Err(syn::Error::new_spanned(value, "expected `true` or `false`")),
+            };
+        }
+        if let (Some(default), true) = (&args.default, args.allow_empty) {
+            return Err(syn::Error::new_spanned(default, "`default` and `allow_empty` cannot be combined"));
+        }
+        if let Some(pattern) = matches {
+            // A bad pattern is an error here rather than on every line read.
+            args.matches = match pattern {
+                Expr::Lit(ExprLit { lit: Lit::Str(pattern), .. }) => match regex::Regex::new(&pattern.value()) {
+                    Ok(_) => Some(pattern),
+                    Err(e) => return Err(syn::Error::new_spanned(pattern, format!("invalid regular expression: {}", e))),
+                },
+                pattern => return Err(syn::Error::new_spanned(pattern, "expected a string literal such as `r\"^[a-z]+$\"`")),
+            };
+        }
+        if let Some(message) = &args.message {
+            if args.validate.is_none() && args.range.is_none() && args.matches.is_none() && args.one_of.is_none() {
+                let error = "`message` needs a `validate`, `range`, `matches` or `one_of` check";
+                return Err(syn::Error::new_spanned(message, error));
+            }
+        }
+        Ok(args)
+    }
+}
+
+/// A `name = value` option, or a `name` flag.
+struct InputOption {
+    name: Ident,
+    value: Option<Expr>,
+}
+
+impl Parse for InputOption {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let name = input.parse()?;
+        if !input.peek(Token![=]) {
+            return Ok(InputOption { name, value: None });
+        }
+        input.parse::<Token![=]>()?;
+        Ok(InputOption { name, value: Some(input.parse()?) })
+    }
+}
+
+impl InputArgs {
+    /// A call to `cio::prompt::read` with the given retries, for a value of
+    /// the inferred type, its default, or an `Option` of it.
+    fn read(&self, retries: TokenStream2) -> TokenStream2 {
+        let prompt = &self.prompt;
+        let checks = self.checks();
+        if let Some(default) = &self.default {
+            return quote! {{
+                #checks
+                let default = #default;
+                let prompt = ::cio::prompt::with_default(#prompt, &default);
+                let mut default = ::std::option::Option::Some(default);
+                ::cio::prompt::read(&prompt, #retries, |line| match line {
+                    "" => ::std::result::Result::Ok(default.take().expect("the default is only used once")),
+                    line => check(line),
+                })
+            }};
+        }
+        if self.allow_empty {
+            return quote! {{
+                #checks
+                ::cio::prompt::read(#prompt, #retries, |line| match line {
+                    "" => ::std::result::Result::Ok(::std::option::Option::None),
+                    line => check(line).map(::std::option::Option::Some),
+                })
+            }};
+        }
+        quote! {{
+            #checks
+            ::cio::prompt::read(#prompt, #retries, check)
+        }}
+    }
+
+    /// Binds `check`, which parses a line, splitting it at `sep` if given,
+    /// and then applies the `matches`, `range`, `one_of` and `validate`
+    /// checks in this order. The option values are evaluated once, before
+    /// the first prompt.
+    fn checks(&self) -> TokenStream2 {
+        let mut names = Vec::new();
+        let mut values = Vec::new();
+        let mut conditions = Vec::new();
+        let mut defaults = Vec::new();
+        let parse = match &self.sep {
+            Some(sep) => {
+                names.push(Ident::new("sep", Span::call_site()));
+                values.push(quote!(#sep));
+                quote!(::cio::prompt::parse_separated(line, ::std::option::Option::Some(sep)))
+            }
+            None => quote!(::cio::prompt::parse(line)),
+        };
+        if let Some(pattern) = &self.matches {
+            names.push(Ident::new("pattern", Span::call_site()));
+            values.push(quote!(::cio::prompt::pattern(#pattern)));
+            conditions.push(quote!(pattern.is_match(line)));
+            defaults.push(quote!(::std::format!("must match `{}`", #pattern)));
+        }
+        if let Some(range) = &self.range {
+            names.push(Ident::new("range", Span::call_site()));
+            values.push(quote!(#range));
+            conditions.push(quote!(::std::ops::RangeBounds::contains(&range, &value)));
+            defaults.push(quote!(::std::format!("must be in {:?}", range)));
+        }
+        if let Some(one_of) = &self.one_of {
+            names.push(Ident::new("options", Span::call_site()));
+            values.push(quote!(#one_of));
+            conditions.push(quote!(::std::iter::IntoIterator::into_iter(&options).any(|option| *option == value)));
+            defaults.push(quote!(::std::format!("must be one of {:?}", options)));
+        }
+        if let Some(validate) = &self.validate {
+            names.push(Ident::new("validate", Span::call_site()));
+            values.push(quote!(#validate));
+            conditions.push(quote!((validate)(&value)));
+            defaults.push(quote!(::std::string::String::from("invalid value")));
+        }
+        let messages: Vec<TokenStream2> = match &self.message {
+            Some(message) => {
+                names.push(Ident::new("message", Span::call_site()));
+                values.push(quote!(::std::string::ToString::to_string(&(#message))));
+                defaults.iter().map(|_| quote!(::std::clone::Clone::clone(&message))).collect()
+            }
+            None => defaults,
+        };
+        if names.is_empty() && conditions.is_empty() {
+            return quote!(let check = ::cio::prompt::parse;);
+        }
+        // The values are bound together so that none of them sees the
+        // names bound for the others.
+        let bindings = (!names.is_empty()).then(|| quote!(let (#(#names,)*) = (#(#values,)*);));
+        quote! {
+            #bindings
+            let check = |line: &str| {
+                let value = match #parse {
+                    ::std::result::Result::Ok(value) => value,
+                    ::std::result::Result::Err(e) => return ::std::result::Result::Err(e),
+                };
+                #(
+                    if !(#conditions) {
+                        return ::std::result::Result::Err(::cio::prompt::InputError::Invalid(#messages));
+                    }
+                )*
+                ::std::result::Result::Ok(value)
+            };
+        }
+    }
+}
+
+/// Expands `input!`, which prompts until the line parses and exits the
+/// program if standard input is closed.
+pub(crate) fn expand_input(args: InputArgs) -> syn::Result<TokenStream2> {
+    if let Some(retries) = &args.retries {
+        let message = "`input!` prompts until the line is valid; use `try_input!` to limit the retries";
+        return Err(syn::Error::new_spanned(retries, message));
+    }
+    let prompt = &args.prompt;
+    let read = args.read(quote!(::std::option::Option::None));
+    Ok(quote! {
+        match #read {
+            ::std::result::Result::Ok(value) => value,
+            ::std::result::Result::Err(e) => ::cio::prompt::exit(#prompt, e),
+        }
+    })
+}
+
+/// Expands `try_input!`, which returns the `Result` after at most `retries`
+/// retries, `cio::prompt::RETRIES` by default.
+pub(crate) fn expand_try_input(args: InputArgs) -> syn::Result<TokenStream2> {
+    let retries = match &args.retries {
+        Some(retries) => quote!(#retries),
+        None => quote!(::cio::prompt::RETRIES),
+    };
+    Ok(args.read(quote!(::std::option::Option::Some(#retries))))
+}
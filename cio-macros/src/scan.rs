@@ -0,0 +1,164 @@
+// scan.rs
+//! Splitting of format strings into placeholders, following the nesting of
+//! the Rust tokens of their expressions like the compiler would.
+
+use std::ops::Range;
+
+/// A `{expr}` or `{expr:spec}` placeholder, as byte ranges of the format string.
+pub(crate) struct Placeholder {
+    /// From the `{` to the `}`, both included.
+    pub(crate) whole: Range<usize>,
+    pub(crate) expr: Range<usize>,
+    /// The text after the `:`, if any.
+    pub(crate) spec: Option<Range<usize>>,
+    /// The expressions of the nested replacement fields of the spec, like
+    /// `w` in `{x:>{w}}`.
+    pub(crate) fields: Vec<Range<usize>>,
+}
+
+/// What the scanner finds in a format string, in order.
+pub(crate) enum Item {
+    Placeholder(Placeholder),
+    /// A `{` that opens no complete placeholder.
+    UnmatchedOpen(usize),
+    /// A `}` that is neither part of a `}}` escape nor the end of a placeholder.
+    UnmatchedClose(usize),
+}
+
+/// Scans `s` for placeholders and unmatched braces. The text around them,
+/// with its `{{` and `}}` escapes, is meant for `std::fmt` as is.
+pub(crate) fn scan(s: &str) -> Vec<Item> {
+    let bytes = s.as_bytes();
+    let mut items = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'{' | b'}' if bytes.get(i + 1) == Some(&bytes[i]) => i += 2,
+            b'}' => {
+                items.push(Item::UnmatchedClose(i));
+                i += 1;
+            }
+            b'{' => match placeholder(s, i) {
+                Some(placeholder) => {
+                    i = placeholder.whole.end;
+                    items.push(Item::Placeholder(placeholder));
+                }
+                None => {
+                    items.push(Item::UnmatchedOpen(i));
+                    i += 1;
+                }
+            },
+            _ => i += 1,
+        }
+    }
+    items
+}
+
+/// Scans the placeholder whose `{` is at `start`.
+fn placeholder(s: &str, start: usize) -> Option<Placeholder> {
+    let bytes = s.as_bytes();
+    let expr_end = skip_expr(s, start + 1, true)?;
+    let expr = start + 1..expr_end;
+    if bytes[expr_end] == b'}' {
+        return Some(Placeholder { whole: start..expr_end + 1, expr, spec: None, fields: Vec::new() });
+    }
+    let spec_start = expr_end + 1;
+    let mut fields = Vec::new();
+    let mut i = spec_start;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'{' => {
+                let end = skip_expr(s, i + 1, false)?;
+                fields.push(i + 1..end);
+                i = end + 1;
+            }
+            b'}' => {
+                let spec = Some(spec_start..i);
+                return Some(Placeholder { whole: start..i + 1, expr, spec, fields });
+            }
+            _ => i += 1,
+        }
+    }
+    None
+}
+
+/// Skips the Rust tokens starting at `start`, returning the offset of the
+/// `}` that ends them, or of the `:` that starts a spec if `spec` is set.
+/// Brackets nest, `::` paths are not a spec, and braces or colons inside
+/// string, raw string and char literals are ignored.
+fn skip_expr(s: &str, start: usize, spec: bool) -> Option<usize> {
+    let bytes = s.as_bytes();
+    let mut depth = 0usize;
+    let mut i = start;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'"' => i = string_end(s, i)?,
+            b'\'' => i = char_end(s, i),
+            b'r' if !follows_ident(bytes, i) => match raw_string_end(s, i) {
+                Some(end) => i = end,
+                None => i += 1,
+            },
+            b'(' | b'[' | b'{' => {
+                depth += 1;
+                i += 1;
+            }
+            b'}' if depth == 0 => return Some(i),
+            b')' | b']' | b'}' => {
+                depth = depth.saturating_sub(1);
+                i += 1;
+            }
+            b':' if bytes.get(i + 1) == Some(&b':') => i += 2,
+            b':' if depth == 0 && spec => return Some(i),
+            _ => i += 1,
+        }
+    }
+    None
+}
+
+fn follows_ident(bytes: &[u8], i: usize) -> bool {
+    i > 0 && (bytes[i - 1].is_ascii_alphanumeric() || bytes[i - 1] == b'_')
+}
+
+/// Returns the offset after the string literal whose `"` is at `start`.
+fn string_end(s: &str, start: usize) -> Option<usize> {
+    let bytes = s.as_bytes();
+    let mut i = start + 1;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 2,
+            b'"' => return Some(i + 1),
+            _ => i += 1,
+        }
+    }
+    None
+}
+
+/// Returns the offset after the raw string literal whose `r` is at
+/// `start`, if there is one, as in `r"..."` or `r#"..."#`.
+fn raw_string_end(s: &str, start: usize) -> Option<usize> {
+    let hashes = s[start + 1..].bytes().take_while(|&b| b == b'#').count();
+    let open = start + 1 + hashes;
+    if s.as_bytes().get(open) != Some(&b'"') {
+        return None;
+    }
+    let close = format!("\"{}", "#".repeat(hashes));
+    s[open + 1..].find(&close).map(|i| open + 1 + i + close.len())
+}
+
+/// Returns the offset after the char literal whose `'` is at `start`, or
+/// just after the `'` of a lifetime or label.
+fn char_end(s: &str, start: usize) -> usize {
+    let rest = &s[start + 1..];
+    let mut chars = rest.char_indices();
+    match chars.next() {
+        Some((_, '\\')) => {
+            chars.next();
+            match chars.find(|&(_, c)| c == '\'') {
+                Some((i, _)) => start + 2 + i,
+                None => start + 1,
+            }
+        }
+        Some((_, c)) if rest[c.len_utf8()..].starts_with('\'') => start + 2 + c.len_utf8(),
+        _ => start + 1,
+    }
+}
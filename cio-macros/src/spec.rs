@@ -0,0 +1,629 @@
+// spec.rs
+//! Format specs accepted after the `:` of a placeholder.
+
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{quote, ToTokens};
+use syn::{parse_quote, Expr};
+
+/// A placeholder format spec.
+pub(crate) enum Spec {
+    /// No spec: `Display`.
+    Display,
+    /// `:a`: depth-adaptive indentation of containers.
+    Container { summary: Summary, sorted: bool },
+    /// `:c`: compact single-line `Debug`.
+    Compact { sorted: bool },
+    /// `:j`: pretty-printed `Debug`.
+    Pretty { summary: Summary, sorted: bool },
+    /// `:m`, `:mi`, `:m.3`: column-aligned matrix, with row and column
+    /// index headers for `i` and a float precision after the `.`.
+    Matrix { index: bool, precision: Option<usize> },
+    /// `:json`, `:json#`: JSON through `serde`, compact or indented.
+    Json { pretty: bool, sorted: bool },
+    /// `:t`, `:tm`: a table of rows, drawn with box characters or in
+    /// Markdown.
+    Table { markdown: bool, sorted: bool },
+    /// Any other spec of the `std::fmt` grammar.
+    Std(String),
+    /// A spec of Python's format mini-language that `std::fmt` cannot
+    /// express, like `,`, `.1%` or `=+10`, formatted at runtime.
+    Number(Box<NumberSpec>),
+    /// A `std::fmt` spec with nested replacement fields, like `{x:>{w}.{p}}`:
+    /// `spec` refers to the width and precision as `1$` and `2$`, and a
+    /// dynamic fill or alignment pads the formatted value at runtime.
+    Nested { spec: String, counts: Vec<Expr>, padding: Option<Padding> },
+}
+
+/// The runtime padding of a `Spec::Nested`, as `char`, `Align` and `usize`
+/// expressions.
+pub(crate) struct Padding {
+    fill: TokenStream2,
+    align: TokenStream2,
+    width: TokenStream2,
+}
+
+/// The nested replacement fields of a spec.
+#[derive(Default)]
+pub(crate) struct Fields {
+    fill: Option<Expr>,
+    align: Option<Expr>,
+    width: Option<Expr>,
+    precision: Option<Expr>,
+}
+
+/// Stands for a nested replacement field in the spec given to
+/// `Spec::parse_nested`.
+pub(crate) const FIELD: char = '\u{1}';
+
+impl Spec {
+    /// Parses the text after the `:` of a placeholder, if it is a known spec.
+    /// The container, JSON and table specs accept a `+sorted` suffix, which
+    /// orders map keys and set elements.
+    pub(crate) fn parse(spec: &str) -> Option<Spec> {
+        let (spec, sorted) = match spec.strip_suffix("+sorted") {
+            Some(spec) => (spec, true),
+            None => (spec, false),
+        };
+        match spec {
+            "c" => return Some(Spec::Compact { sorted }),
+            "json" => return Some(Spec::Json { pretty: false, sorted }),
+            "json#" => return Some(Spec::Json { pretty: true, sorted }),
+            "t" => return Some(Spec::Table { markdown: false, sorted }),
+            "tm" => return Some(Spec::Table { markdown: true, sorted }),
+            _ => {}
+        }
+        // Other specs starting with these letters, like the `a` fill of
+        // `{x:a<5}`, are left to `std::fmt`.
+        if let Some(summary) = spec.strip_prefix('a').and_then(Summary::parse) {
+            return Some(Spec::Container { summary, sorted });
+        }
+        if let Some(summary) = spec.strip_prefix('j').and_then(Summary::parse) {
+            return Some(Spec::Pretty { summary, sorted });
+        }
+        if sorted {
+            return None;
+        }
+        if let Some(matrix) = Spec::parse_matrix(spec) {
+            return Some(matrix);
+        }
+        if is_std_spec(spec) {
+            return Some(Spec::Std(spec.to_string()));
+        }
+        let number = NumberSpec::parse(spec)?;
+        match number.kind {
+            Some('s') => Some(Spec::Std(number.to_std_string_spec())),
+            _ => Some(Spec::Number(Box::new(number))),
+        }
+    }
+
+    /// Parses `m`, `mi`, `m.N` and `mi.N`.
+    fn parse_matrix(spec: &str) -> Option<Spec> {
+        let rest = spec.strip_prefix('m')?;
+        let (index, rest) = match rest.strip_prefix('i') {
+            Some(rest) => (true, rest),
+            None => (false, rest),
+        };
+        let precision = match rest.strip_prefix('.') {
+            Some(digits) if !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit()) => Some(digits.parse().ok()?),
+            Some(_) => return None,
+            None if rest.is_empty() => None,
+            None => return None,
+        };
+        Some(Spec::Matrix { index, precision })
+    }
+
+    /// Parses a spec whose nested replacement fields, evaluated to `exprs`,
+    /// were replaced by `FIELD`. As in Python, they may stand for the fill,
+    /// the alignment, the width and the precision: a field is an alignment
+    /// if another field or a sign, `#`, `0` or width follows it, and a fill
+    /// if an alignment follows it.
+    pub(crate) fn parse_nested(spec: &str, exprs: Vec<Expr>) -> Result<Spec, String> {
+        let chars: Vec<char> = spec.chars().collect();
+        let is = |i: usize, f: fn(char) -> bool| chars.get(i).is_some_and(|&c| f(c));
+        let field = |c| c == FIELD;
+        let align = |c| matches!(c, '<' | '>' | '^' | '=');
+        let digit = |c: char| c.is_ascii_digit();
+        let flag = |c| matches!(c, '+' | '-' | ' ' | 'z' | '#' | '0'..='9');
+        let (fill_at, align_at) = if is(1, align) {
+            (Some(0), Some(1))
+        } else if is(0, align) {
+            (None, Some(0))
+        } else if is(0, field) && is(1, field) && (is(2, field) || is(2, digit)) {
+            (Some(0), Some(1))
+        } else if is(0, field) && (is(1, field) || is(1, flag)) {
+            (None, Some(0))
+        } else {
+            (None, None)
+        };
+        let mut exprs = exprs.into_iter();
+        let mut fields = Fields::default();
+        let mut representative = String::new();
+        let mut pos = 0;
+        if let Some(at) = fill_at {
+            match chars[at] {
+                FIELD => {
+                    fields.fill = exprs.next();
+                    representative.push(' ');
+                }
+                c => representative.push(c),
+            }
+            pos = at + 1;
+        }
+        if let Some(at) = align_at {
+            match chars[at] {
+                FIELD => {
+                    fields.align = exprs.next();
+                    representative.push('>');
+                }
+                c => representative.push(c),
+            }
+            pos = at + 1;
+        }
+        let flags_start = pos;
+        for flag in [|c| matches!(c, '+' | '-' | ' '), |c| c == 'z', |c| c == '#', |c| c == '0'] {
+            if is(pos, flag) {
+                pos += 1;
+            }
+        }
+        let flags: String = chars[flags_start..pos].iter().collect();
+        let width_start = pos;
+        if is(pos, field) {
+            fields.width = exprs.next();
+            pos += 1;
+        } else {
+            while is(pos, digit) {
+                pos += 1;
+            }
+        }
+        let width: String = chars[width_start..pos].iter().collect();
+        let grouping_start = pos;
+        if is(pos, |c| matches!(c, ',' | '_')) {
+            pos += 1;
+        }
+        let grouping: String = chars[grouping_start..pos].iter().collect();
+        let mut precision = None;
+        if is(pos, |c| c == '.') {
+            let start = pos + 1;
+            pos = start;
+            if is(pos, field) {
+                fields.precision = exprs.next();
+                pos += 1;
+            } else {
+                while is(pos, digit) {
+                    pos += 1;
+                }
+            }
+            precision = Some(chars[start..pos].iter().collect::<String>());
+        }
+        let kind: String = chars[pos..].iter().collect();
+        if kind.contains(FIELD) {
+            return Err("a nested replacement field may only stand for the fill, alignment, width or precision".to_string());
+        }
+        representative.push_str(&flags);
+        representative.push_str(&width.replace(FIELD, "1"));
+        representative.push_str(&grouping);
+        if let Some(precision) = &precision {
+            representative.push('.');
+            representative.push_str(&precision.replace(FIELD, "1"));
+        }
+        representative.push_str(&kind);
+        match Spec::parse(&representative) {
+            Some(Spec::Number(number)) => Ok(Spec::Number(Box::new(NumberSpec { fields, ..*number }))),
+            Some(Spec::Std(_)) => {
+                let kind = kind.strip_suffix('s').unwrap_or(&kind);
+                Ok(Spec::nested_std(&chars, fill_at, align_at, &flags, &width, precision.as_deref(), kind, fields))
+            }
+            Some(_) => Err("nested replacement fields are only supported in `std::fmt` and Python number specs".to_string()),
+            None => Err(format!("unknown format spec `{}`", spec.replace(FIELD, "{..}"))),
+        }
+    }
+
+    /// Builds a `Spec::Nested` from the parts of a spec checked by
+    /// `parse_nested`.
+    #[allow(clippy::too_many_arguments)]
+    fn nested_std(
+        chars: &[char],
+        fill_at: Option<usize>,
+        align_at: Option<usize>,
+        flags: &str,
+        width: &str,
+        precision: Option<&str>,
+        kind: &str,
+        fields: Fields,
+    ) -> Spec {
+        let mut counts = Vec::new();
+        let mut count = |text: &str, expr: Option<Expr>| match expr {
+            Some(expr) => {
+                counts.push(expr);
+                format!("{}$", counts.len())
+            }
+            None => text.to_string(),
+        };
+        let precision = precision.map(|precision| format!(".{}", count(precision, fields.precision)));
+        let precision = precision.unwrap_or_default();
+        if fields.fill.is_none() && fields.align.is_none() {
+            let mut spec: String = fill_at.into_iter().chain(align_at).map(|at| chars[at]).collect();
+            spec.push_str(flags);
+            spec.push_str(&count(width, fields.width));
+            spec.push_str(&precision);
+            spec.push_str(kind);
+            return Spec::Nested { spec, counts, padding: None };
+        }
+        // `std::fmt` has no dynamic fill nor alignment: the value is formatted
+        // without them, then padded at runtime.
+        let zero = flags.contains('0');
+        let fill = match (fields.fill, fill_at.map(|at| chars[at])) {
+            (Some(fill), _) => quote!(#fill),
+            (None, Some(fill)) => quote!(#fill),
+            (None, None) => {
+                let fill = if zero { '0' } else { ' ' };
+                quote!(#fill)
+            }
+        };
+        let align = match (fields.align, align_at.map(|at| chars[at])) {
+            (Some(align), _) => quote!(::cio::number::Align::from_char(#align)),
+            (None, align) => {
+                let align = align.unwrap_or('>');
+                quote!(::cio::number::Align::from_char(#align))
+            }
+        };
+        let width = match fields.width {
+            Some(width) => quote!(#width),
+            None => {
+                let width: usize = width.parse().unwrap_or(0);
+                quote!(#width)
+            }
+        };
+        let spec = format!("{}{}{}", flags.replace('0', ""), precision, kind);
+        Spec::Nested { spec, counts, padding: Some(Padding { fill, align, width }) }
+    }
+
+    /// Returns whether this spec applies to the string a conversion flag
+    /// produces.
+    pub(crate) fn applies_to_text(&self) -> bool {
+        matches!(self, Spec::Display | Spec::Std(_) | Spec::Nested { .. })
+    }
+
+    /// Returns the `std::fmt` placeholder and the argument that format
+    /// `expr` according to this spec.
+    pub(crate) fn expand(&self, expr: &Expr) -> (&'static str, TokenStream2) {
+        match self {
+            Spec::Display => ("{}", quote!(#expr)),
+            Spec::Container { summary, sorted } => {
+                ("{}", quote!(::cio::container::format_container(&(#expr), #summary, #sorted)))
+            }
+            Spec::Compact { sorted: false } => ("{}", quote!(format!("{:?}", #expr))),
+            Spec::Compact { sorted: true } => ("{}", quote!(::cio::container::format_compact(&(#expr), true))),
+            Spec::Pretty { summary, sorted } => {
+                ("{}", quote!(::cio::container::format_pretty(&(#expr), #summary, #sorted)))
+            }
+            Spec::Matrix { index, precision } => {
+                let precision = match precision {
+                    Some(precision) => quote!(::std::option::Option::Some(#precision)),
+                    None => quote!(::std::option::Option::None),
+                };
+                ("{}", quote!(::cio::container::format_matrix(&(#expr), #precision, #index)))
+            }
+            Spec::Json { pretty, sorted } => ("{}", quote!(::cio::json::format_json(&(#expr), #pretty, #sorted))),
+            Spec::Table { markdown, sorted } => {
+                ("{}", quote!(::cio::table::format_table(&(#expr), #markdown, #sorted)))
+            }
+            Spec::Std(spec) => {
+                let spec_fmt = format!("{{:{}}}", spec);
+                ("{}", quote!(format!(#spec_fmt, #expr)))
+            }
+            Spec::Nested { spec, counts, padding } => {
+                let spec_fmt = format!("{{:{}}}", spec);
+                let formatted = quote!(format!(#spec_fmt, #expr, #(#counts),*));
+                match padding {
+                    None => ("{}", formatted),
+                    Some(Padding { fill, align, width }) => {
+                        ("{}", quote!(::cio::number::pad(&#formatted, #fill, #align, #width)))
+                    }
+                }
+            }
+            Spec::Number(spec) if spec.is_integer() => ("{}", quote!(::cio::number::format_integer(&(#expr), #spec))),
+            Spec::Number(spec) => ("{}", quote!(::cio::number::format_number(&(#expr), #spec))),
+        }
+    }
+}
+
+/// A conversion flag, `!r`, `!s` or `!a`, applied to the value before its
+/// spec as in Python.
+#[derive(Clone, Copy)]
+pub(crate) enum Conversion {
+    /// `!r`: `Debug`, the counterpart of Python's `repr()`.
+    Repr,
+    /// `!s`: `Display`, the counterpart of Python's `str()`.
+    Str,
+    /// `!a`: `Debug` with non-ASCII characters escaped as `\u{..}`.
+    Ascii,
+}
+
+impl Conversion {
+    /// Splits a trailing conversion flag off the expression text of a
+    /// placeholder, returning the length of the expression.
+    pub(crate) fn split(text: &str) -> Option<(usize, Conversion)> {
+        let text = text.trim_end();
+        let expr_len = text.len().checked_sub(2)?;
+        let conversion = match text.get(expr_len..)? {
+            "!r" => Conversion::Repr,
+            "!s" => Conversion::Str,
+            "!a" => Conversion::Ascii,
+            _ => return None,
+        };
+        Some((expr_len, conversion))
+    }
+
+    /// Returns the expression of the converted `String`.
+    pub(crate) fn apply(self, expr: &Expr) -> Expr {
+        match self {
+            Conversion::Repr => parse_quote!(format!("{:?}", #expr)),
+            Conversion::Str => parse_quote!(format!("{}", #expr)),
+            Conversion::Ascii => parse_quote!(::cio::conversion::format_ascii(&(#expr))),
+        }
+    }
+}
+
+/// Overrides of the runtime summarization settings, from the `<...>` that
+/// may follow `:a` and `:j`, like `{v:a<edge=3, threshold=100>}`.
+#[derive(Default)]
+pub(crate) struct Summary {
+    edge: Option<usize>,
+    threshold: Option<usize>,
+}
+
+impl Summary {
+    /// Parses the text following `a` or `j` in a spec.
+    fn parse(spec: &str) -> Option<Summary> {
+        let mut summary = Summary::default();
+        if spec.is_empty() {
+            return Some(summary);
+        }
+        let settings = spec.strip_prefix('<')?.strip_suffix('>')?;
+        for setting in settings.split(',') {
+            let (key, value) = setting.split_once('=')?;
+            let value = Some(value.trim().parse().ok()?);
+            match key.trim() {
+                "edge" if summary.edge.is_none() => summary.edge = value,
+                "threshold" if summary.threshold.is_none() => summary.threshold = value,
+                _ => return None,
+            }
+        }
+        Some(summary)
+    }
+}
+
+impl ToTokens for Summary {
+    fn to_tokens(&self, tokens: &mut TokenStream2) {
+        let edge = self.edge.map(|edge| quote!(edge: #edge,));
+        let threshold = self.threshold.map(|threshold| quote!(threshold: #threshold,));
+        tokens.extend(match (&edge, &threshold) {
+            (None, None) => quote!(::cio::container::summary()),
+            (Some(_), Some(_)) => quote!(::cio::container::Summary { #edge #threshold }),
+            _ => quote!(::cio::container::Summary { #edge #threshold ..::cio::container::summary() }),
+        });
+    }
+}
+
+/// A spec of Python's format mini-language:
+/// `[[fill]align][sign][z][#][0][width][grouping][.precision][type]`.
+pub(crate) struct NumberSpec {
+    fill: Option<char>,
+    align: Option<char>,
+    sign: Option<char>,
+    coerce_zero: bool,
+    alternate: bool,
+    zero: bool,
+    width: usize,
+    grouping: Option<char>,
+    precision: Option<usize>,
+    kind: Option<char>,
+    fields: Fields,
+}
+
+impl NumberSpec {
+    /// Parses `spec`, rejecting the combinations Python rejects, like a
+    /// precision with an integer type or `,` with a hexadecimal one.
+    fn parse(spec: &str) -> Option<NumberSpec> {
+        let mut chars = spec.chars().peekable();
+        let mut fill = None;
+        let mut align = None;
+        let mut lookahead = spec.chars();
+        let (first, second) = (lookahead.next(), lookahead.next());
+        if matches!(second, Some('<' | '>' | '^' | '=')) {
+            fill = first;
+            align = second;
+            chars.nth(1);
+        } else if matches!(first, Some('<' | '>' | '^' | '=')) {
+            align = first;
+            chars.next();
+        }
+        let sign = chars.next_if(|&c| matches!(c, '+' | '-' | ' '));
+        let coerce_zero = chars.next_if_eq(&'z').is_some();
+        let alternate = chars.next_if_eq(&'#').is_some();
+        let zero = chars.next_if_eq(&'0').is_some();
+        let width = digits(&mut chars).map_or(Some(0), |width| width.parse().ok())?;
+        let grouping = chars.next_if(|&c| matches!(c, ',' | '_'));
+        let precision = match chars.next_if_eq(&'.') {
+            Some(_) => Some(digits(&mut chars)?.parse().ok()?),
+            None => None,
+        };
+        let kind = chars.next_if(|c| "bcdeEfFgGosxX%".contains(*c));
+        if chars.next().is_some() {
+            return None;
+        }
+        let fields = Fields::default();
+        let number = NumberSpec { fill, align, sign, coerce_zero, alternate, zero, width, grouping, precision, kind, fields };
+        number.is_valid().then_some(number)
+    }
+
+    fn is_valid(&self) -> bool {
+        match self.kind {
+            Some('s') => {
+                self.align != Some('=')
+                    && self.sign.is_none()
+                    && !self.coerce_zero
+                    && !self.alternate
+                    && !self.zero
+                    && self.grouping.is_none()
+            }
+            Some('c') => {
+                self.sign.is_none() && !self.coerce_zero && !self.alternate && self.grouping.is_none() && self.precision.is_none()
+            }
+            Some('b' | 'o' | 'x' | 'X') => {
+                !self.coerce_zero && self.precision.is_none() && self.grouping != Some(',')
+            }
+            Some('d') => !self.coerce_zero && self.precision.is_none(),
+            _ => true,
+        }
+    }
+
+    fn is_integer(&self) -> bool {
+        matches!(self.kind, Some('d' | 'b' | 'o' | 'x' | 'X' | 'c'))
+    }
+
+    /// Translates an `s` spec, which only has a fill, an alignment, a width
+    /// and a precision, to `std::fmt`.
+    fn to_std_string_spec(&self) -> String {
+        let mut spec = String::new();
+        if let Some(align) = self.align {
+            spec.extend(self.fill);
+            spec.push(align);
+        }
+        if self.width > 0 {
+            spec.push_str(&self.width.to_string());
+        }
+        if let Some(precision) = self.precision {
+            spec.push_str(&format!(".{}", precision));
+        }
+        spec
+    }
+}
+
+impl ToTokens for NumberSpec {
+    fn to_tokens(&self, tokens: &mut TokenStream2) {
+        let fill = self.fill.unwrap_or(if self.zero { '0' } else { ' ' });
+        let align = match self.align {
+            Some('<') => quote!(Left),
+            Some('^') => quote!(Center),
+            Some('=') => quote!(AfterSign),
+            Some(_) => quote!(Right),
+            None if self.zero => quote!(AfterSign),
+            None => quote!(Right),
+        };
+        let sign = match self.sign {
+            Some('+') => quote!(Plus),
+            Some(' ') => quote!(Space),
+            _ => quote!(Minus),
+        };
+        let fill = match &self.fields.fill {
+            Some(fill) => quote!(#fill),
+            None => quote!(#fill),
+        };
+        let align = match &self.fields.align {
+            Some(align) => quote!(::cio::number::Align::from_char(#align)),
+            None => quote!(::cio::number::Align::#align),
+        };
+        let width = match &self.fields.width {
+            Some(width) => quote!(#width),
+            None => {
+                let width = self.width;
+                quote!(#width)
+            }
+        };
+        let (coerce_zero, alternate) = (self.coerce_zero, self.alternate);
+        let grouping = match self.grouping {
+            Some(grouping) => quote!(::std::option::Option::Some(#grouping)),
+            None => quote!(::std::option::Option::None),
+        };
+        let precision = match (&self.fields.precision, self.precision) {
+            (Some(precision), _) => quote!(::std::option::Option::Some(#precision)),
+            (None, Some(precision)) => quote!(::std::option::Option::Some(#precision)),
+            (None, None) => quote!(::std::option::Option::None),
+        };
+        let kind = match self.kind {
+            Some(kind) => {
+                let kind = match kind {
+                    'd' => quote!(Decimal),
+                    'b' => quote!(Binary),
+                    'o' => quote!(Octal),
+                    'x' => quote!(Hex),
+                    'X' => quote!(UpperHex),
+                    'c' => quote!(Char),
+                    'e' => quote!(Exponent),
+                    'E' => quote!(UpperExponent),
+                    'f' => quote!(Fixed),
+                    'F' => quote!(UpperFixed),
+                    'g' => quote!(General),
+                    'G' => quote!(UpperGeneral),
+                    _ => quote!(Percent),
+                };
+                quote!(::std::option::Option::Some(::cio::number::Kind::#kind))
+            }
+            None => quote!(::std::option::Option::None),
+        };
+        tokens.extend(quote!(::cio::number::NumberSpec {
+            fill: #fill,
+            align: #align,
+            sign: ::cio::number::Sign::#sign,
+            coerce_zero: #coerce_zero,
+            alternate: #alternate,
+            width: #width,
+            grouping: #grouping,
+            precision: #precision,
+            kind: #kind,
+        }));
+    }
+}
+
+/// Consumes the decimal digits at the start of `chars`, if any.
+fn digits(chars: &mut std::iter::Peekable<std::str::Chars>) -> Option<String> {
+    let mut digits = String::new();
+    while let Some(digit) = chars.next_if(char::is_ascii_digit) {
+        digits.push(digit);
+    }
+    (!digits.is_empty()).then_some(digits)
+}
+
+/// Returns whether `spec` follows the `std::fmt` format spec grammar:
+/// `[[fill]align][sign]['#']['0'][width]['.' precision][type]`.
+fn is_std_spec(spec: &str) -> bool {
+    let mut rest = spec;
+    let mut chars = rest.chars();
+    let first = chars.next();
+    let second = chars.next();
+    if matches!(second, Some('<' | '^' | '>')) {
+        rest = &rest[first.map_or(0, char::len_utf8) + 1..];
+    } else if matches!(first, Some('<' | '^' | '>')) {
+        rest = &rest[1..];
+    }
+    rest = rest.strip_prefix(['+', '-']).unwrap_or(rest);
+    rest = rest.strip_prefix('#').unwrap_or(rest);
+    rest = rest.strip_prefix('0').unwrap_or(rest);
+    rest = skip_count(rest);
+    if let Some(precision) = rest.strip_prefix('.') {
+        let after = skip_count(precision);
+        if after.len() == precision.len() {
+            return false;
+        }
+        rest = after;
+    }
+    matches!(rest, "" | "?" | "x?" | "X?" | "x" | "X" | "o" | "b" | "e" | "E" | "p")
+}
+
+/// Skips a `count` (`integer` or `identifier$`) at the start of `s`. The
+/// positional `integer$` and `.*` counts refer to arguments the macro does
+/// not pass, so they are left for the caller to reject: the nested
+/// `{expr}` form gives a dynamic width or precision instead.
+fn skip_count(s: &str) -> &str {
+    let digits = s.len() - s.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    if digits > 0 {
+        return &s[digits..];
+    }
+    let ident = s.len() - s.trim_start_matches(|c: char| c == '_' || c.is_alphanumeric()).len();
+    match s[ident..].strip_prefix('$') {
+        Some(after) if ident > 0 => after,
+        _ => s,
+    }
+}
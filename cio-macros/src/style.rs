@@ -0,0 +1,145 @@
+// style.rs
+//! The `[bold red]...[/]` markup of format strings and the style words of
+//! `{x:green}` specs, turned into ANSI escape sequences.
+
+use std::ops::Range;
+
+/// What the markup scanner finds in the literal text of a format string.
+pub(crate) enum Markup {
+    Text(Range<usize>),
+    /// A `[style]` tag, with the SGR parameters of its style.
+    Open { tag: String, sgr: String },
+    /// A `[/]` tag, or `[/style]` naming the tag it closes.
+    Close { range: Range<usize>, tag: Option<String> },
+}
+
+/// Splits `text` into text and tags. Brackets whose content is not a style,
+/// like `[1, 2]`, are text, and `\[` prints a `[` that would open a tag.
+pub(crate) fn markup(text: &str) -> Vec<Markup> {
+    let mut items = Vec::new();
+    let mut last = 0;
+    let mut i = 0;
+    while let Some(offset) = text[i..].find('[') {
+        let open = i + offset;
+        let Some(close) = text[open..].find(']').map(|end| open + end) else {
+            break;
+        };
+        let content = &text[open + 1..close];
+        let item = match content.strip_prefix('/') {
+            Some("") => Some(Markup::Close { range: open..close + 1, tag: None }),
+            Some(tag) => parse_style(tag).map(|_| Markup::Close { range: open..close + 1, tag: Some(tag.to_string()) }),
+            None => parse_style(content).map(|sgr| Markup::Open { tag: content.to_string(), sgr }),
+        };
+        let Some(item) = item else {
+            i = open + 1;
+            continue;
+        };
+        if open > 0 && text.as_bytes()[open - 1] == b'\\' {
+            items.push(Markup::Text(last..open - 1));
+            last = open;
+        } else {
+            items.push(Markup::Text(last..open));
+            items.push(item);
+            last = close + 1;
+        }
+        i = close + 1;
+    }
+    items.push(Markup::Text(last..text.len()));
+    items
+}
+
+/// Returns the SGR parameters of a style made of space-separated words such
+/// as `bold red on white`, or `None` if any word is not a style.
+pub(crate) fn parse_style(style: &str) -> Option<String> {
+    let mut params = Vec::new();
+    let mut words = style.split(' ').filter(|word| !word.is_empty()).peekable();
+    words.peek()?;
+    while let Some(word) = words.next() {
+        let (word, background) = match word {
+            "on" => (words.next()?, true),
+            word => match word.strip_prefix("on_") {
+                Some(color) => (color, true),
+                None => (word, false),
+            },
+        };
+        let attribute = match word {
+            "bold" => Some(1),
+            "dim" => Some(2),
+            "italic" => Some(3),
+            "underline" => Some(4),
+            "blink" => Some(5),
+            "reverse" => Some(7),
+            "hidden" => Some(8),
+            "strike" => Some(9),
+            _ => None,
+        };
+        match attribute {
+            Some(code) if !background => params.push(code.to_string()),
+            Some(_) => return None,
+            None => params.push(color(word, background)?),
+        }
+    }
+    Some(params.join(";"))
+}
+
+/// Returns the SGR parameter of a named or `#rrggbb` color.
+fn color(word: &str, background: bool) -> Option<String> {
+    if let Some(hex) = word.strip_prefix('#') {
+        if hex.len() != 6 || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return None;
+        }
+        let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).unwrap_or(0);
+        let layer = if background { 48 } else { 38 };
+        return Some(format!("{};2;{};{};{}", layer, channel(0), channel(2), channel(4)));
+    }
+    const COLORS: [&str; 8] = ["black", "red", "green", "yellow", "blue", "magenta", "cyan", "white"];
+    let (name, bright) = match word.strip_prefix("bright_") {
+        Some(name) => (name, true),
+        None => (word, false),
+    };
+    let index = match name {
+        "grey" | "gray" if !bright => return Some(if background { "100" } else { "90" }.to_string()),
+        "default" if !bright => return Some(if background { "49" } else { "39" }.to_string()),
+        name => COLORS.iter().position(|&color| color == name)?,
+    };
+    let base = match (background, bright) {
+        (false, false) => 30,
+        (true, false) => 40,
+        (false, true) => 90,
+        (true, true) => 100,
+    };
+    Some((base + index).to_string())
+}
+
+/// Splits the style words off the end of a placeholder spec, as in
+/// `{x:green}` or `{x:>8.2f bold green}`, returning the length of the
+/// format spec before them and their SGR parameters.
+pub(crate) fn split_spec(spec: &str) -> Option<(usize, String)> {
+    if let Some(sgr) = parse_style(spec) {
+        return Some((0, sgr));
+    }
+    // The longest run of trailing words that is a style, keeping a format
+    // spec before it that may start with a space fill.
+    let mut split = None;
+    for (i, _) in spec.match_indices(' ').filter(|&(i, _)| i > 0) {
+        if let Some(sgr) = parse_style(&spec[i + 1..]) {
+            split = Some((i, sgr));
+            break;
+        }
+    }
+    split
+}
+
+/// The escape sequence that sets the SGR parameters `sgr`.
+pub(crate) fn sequence(sgr: &str) -> String {
+    format!("\x1b[{}m", sgr)
+}
+
+/// The escape sequence that ends a style, then restores the `open` ones.
+pub(crate) fn reset(open: &[(String, String)]) -> String {
+    let mut escape = sequence("0");
+    for (_, sgr) in open {
+        escape.push_str(&sequence(sgr));
+    }
+    escape
+}
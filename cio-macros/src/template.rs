@@ -0,0 +1,222 @@
+// template.rs
+//! Format strings given as a string literal, or as `concat!` and
+//! `include_str!` calls that the macros evaluate themselves, since a
+//! procedural macro only sees the tokens of its input.
+
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use syn::parse::{Parse, ParseStream};
+use syn::{Lit, LitStr, Macro, Token};
+
+/// The format string of a macro call, with where each part of its value
+/// comes from.
+pub(crate) struct Template {
+    pub(crate) value: String,
+    pieces: Vec<Piece>,
+}
+
+/// A part of a template value, starting at byte `start`.
+struct Piece {
+    start: usize,
+    source: Source,
+}
+
+enum Source {
+    /// A string literal, with the source offset of each byte of its value.
+    Str { lit: LitStr, offsets: Vec<usize> },
+    /// A char, integer, float or bool literal of `concat!`.
+    Token(Span),
+    /// A file read at compile time: errors point at the path literal and
+    /// give the line and column in the file.
+    File { path: LitStr, absolute: PathBuf },
+    /// Text built by a macro, which errors point at without a position.
+    Generated(Span),
+}
+
+impl Template {
+    pub(crate) fn literal(lit: &LitStr) -> Template {
+        let mut template = Template { value: String::new(), pieces: Vec::new() };
+        template.push_str(lit);
+        template
+    }
+
+    /// A template made by a macro from `value`, such as the placeholder of
+    /// a `#[cio(spec = "...")]` attribute.
+    pub(crate) fn generated(value: String, span: Span) -> Template {
+        Template { value, pieces: vec![Piece { start: 0, source: Source::Generated(span) }] }
+    }
+
+    /// Reads the template file at `path`, relative to the directory of the
+    /// crate being compiled, for `printf_file!`.
+    pub(crate) fn file(path: &LitStr) -> syn::Result<Template> {
+        let dir = std::env::var("CARGO_MANIFEST_DIR").unwrap_or_default();
+        let mut template = Template { value: String::new(), pieces: Vec::new() };
+        template.push_file(path, Path::new(&dir).join(path.value()))?;
+        Ok(template)
+    }
+
+    fn push_str(&mut self, lit: &LitStr) {
+        let source = Source::Str { lit: lit.clone(), offsets: source_offsets(lit) };
+        self.pieces.push(Piece { start: self.value.len(), source });
+        self.value.push_str(&lit.value());
+    }
+
+    fn push_file(&mut self, path: &LitStr, absolute: PathBuf) -> syn::Result<()> {
+        let text = std::fs::read_to_string(&absolute)
+            .map_err(|e| syn::Error::new(path.span(), format!("couldn't read `{}`: {}", absolute.display(), e)))?;
+        let absolute = std::fs::canonicalize(&absolute).unwrap_or(absolute);
+        self.pieces.push(Piece { start: self.value.len(), source: Source::File { path: path.clone(), absolute } });
+        self.value.push_str(&text);
+        Ok(())
+    }
+
+    /// Parses a string literal, or a `concat!` or `include_str!` call.
+    fn parse_piece(&mut self, input: ParseStream, in_concat: bool) -> syn::Result<()> {
+        if input.peek(LitStr) {
+            self.push_str(&input.parse()?);
+            return Ok(());
+        }
+        if in_concat && input.peek(Lit) {
+            let lit: Lit = input.parse()?;
+            let text = match &lit {
+                Lit::Char(c) => c.value().to_string(),
+                Lit::Int(int) => int.base10_digits().to_string(),
+                Lit::Float(float) => float.base10_digits().to_string(),
+                Lit::Bool(bool) => bool.value.to_string(),
+                _ => return Err(syn::Error::new(lit.span(), "cannot concatenate a byte string literal")),
+            };
+            self.pieces.push(Piece { start: self.value.len(), source: Source::Token(lit.span()) });
+            self.value.push_str(&text);
+            return Ok(());
+        }
+        let expected = "expected a string literal, `concat!(..)` or `include_str!(..)`; \
+                        the value of a `const` or a variable is not known to the macro";
+        if !(input.peek(syn::Ident) && input.peek2(Token![!])) {
+            return Err(input.error(expected));
+        }
+        let mac: Macro = input.parse()?;
+        if mac.path.is_ident("concat") {
+            mac.parse_body_with(|input: ParseStream| {
+                while !input.is_empty() {
+                    self.parse_piece(input, true)?;
+                    if !input.is_empty() {
+                        input.parse::<Token![,]>()?;
+                    }
+                }
+                Ok(())
+            })
+        } else if mac.path.is_ident("include_str") {
+            let path: LitStr = mac.parse_body()?;
+            // Like `include_str!`, relative to the file of the call.
+            let dir = path.span().unwrap().local_file().and_then(|file| file.parent().map(Path::to_path_buf));
+            let dir = dir.unwrap_or_else(|| std::env::var("CARGO_MANIFEST_DIR").unwrap_or_default().into());
+            self.push_file(&path, dir.join(path.value()))
+        } else {
+            Err(syn::Error::new_spanned(mac.path, expected))
+        }
+    }
+
+    /// Span of `range` of the value: inside a string literal where the
+    /// compiler can subspan it, or else the whole token it comes from.
+    pub(crate) fn span(&self, range: Range<usize>) -> Span {
+        let piece = self.piece(range.start);
+        match &piece.source {
+            Source::Str { lit, offsets } => {
+                let end = range.end.min(piece.start + offsets.len() - 1);
+                let (start, end) = (offsets[range.start - piece.start], offsets[end - piece.start]);
+                lit.token().subspan(start..end).unwrap_or_else(|| lit.span())
+            }
+            Source::Token(span) | Source::Generated(span) => *span,
+            Source::File { path, .. } => path.span(),
+        }
+    }
+
+    /// Where `offset` lies, for error messages: the `file:line:column` in a
+    /// template file, or else the line and column in the format string,
+    /// unless a macro generated it.
+    pub(crate) fn location(&self, offset: usize) -> Option<String> {
+        let piece = self.piece(offset);
+        let (start, path) = match &piece.source {
+            Source::File { path, .. } => (piece.start, Some(path)),
+            Source::Generated(_) => return None,
+            _ => (0, None),
+        };
+        let before = &self.value[start..offset];
+        let line = before.matches('\n').count() + 1;
+        let column = before.rsplit('\n').next().unwrap_or("").chars().count() + 1;
+        Some(match path {
+            Some(path) => format!("{}:{}:{}", path.value(), line, column),
+            None if line == 1 => format!("column {}", column),
+            None => format!("line {}, column {}", line, column),
+        })
+    }
+
+    /// Items making the expansion depend on the template files, so that
+    /// editing them rebuilds the crate.
+    pub(crate) fn dependencies(&self) -> TokenStream2 {
+        let files = self.pieces.iter().filter_map(|piece| match &piece.source {
+            Source::File { absolute, .. } => absolute.to_str(),
+            _ => None,
+        });
+        quote!(#(const _: &str = include_str!(#files);)*)
+    }
+
+    fn piece(&self, offset: usize) -> &Piece {
+        let index = self.pieces.partition_point(|piece| piece.start <= offset);
+        &self.pieces[index.saturating_sub(1)]
+    }
+}
+
+impl Parse for Template {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut template = Template { value: String::new(), pieces: Vec::new() };
+        template.parse_piece(input, false)?;
+        if template.pieces.is_empty() {
+            template.pieces.push(Piece { start: 0, source: Source::Token(input.span()) });
+        }
+        Ok(template)
+    }
+}
+
+/// Maps each byte offset of `lit.value()` (plus its end) to the offset of
+/// the source text it was unescaped from.
+fn source_offsets(lit: &LitStr) -> Vec<usize> {
+    let src = lit.token().to_string();
+    let bytes = src.as_bytes();
+    if bytes[0] == b'r' {
+        let hashes = bytes[1..].iter().take_while(|&&b| b == b'#').count();
+        return (2 + hashes..=src.len() - 1 - hashes).collect();
+    }
+    let end = src.rfind('"').unwrap_or(src.len());
+    let mut offsets = Vec::with_capacity(end);
+    let mut i = 1;
+    while i < end {
+        let (produced, next) = if bytes[i] == b'\\' {
+            match bytes[i + 1] {
+                b'x' => (1, i + 4),
+                b'u' => {
+                    let close = i + src[i..].find('}').unwrap_or(0);
+                    let c = u32::from_str_radix(&src[i + 3..close].replace('_', ""), 16)
+                        .ok()
+                        .and_then(char::from_u32)
+                        .unwrap_or('\0');
+                    (c.len_utf8(), close + 1)
+                }
+                b'\n' | b'\r' => {
+                    let skipped = src[i + 1..end].len() - src[i + 1..end].trim_start().len();
+                    (0, i + 1 + skipped)
+                }
+                _ => (1, i + 2),
+            }
+        } else {
+            let len = src[i..].chars().next().map_or(1, char::len_utf8);
+            (len, i + len)
+        };
+        offsets.extend((0..produced).map(|k| if next - i == produced { i + k } else { i }));
+        i = next;
+    }
+    offsets.push(end);
+    offsets
+}
@@ -0,0 +1,183 @@
+// container.rs
+//! Runtime formatters behind the container specs of the formatting macros.
+
+use crate::debug_tree::Node;
+use std::fmt::Debug;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// How the `:a` and `:j` specs shorten long sequences and maps, NumPy style:
+/// each sequence or map with more than `threshold` items, at any nesting
+/// level, only shows its first and last `edge` items around a `...`,
+/// followed by its item count.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Summary {
+    pub threshold: usize,
+    pub edge: usize,
+}
+
+impl Default for Summary {
+    fn default() -> Self {
+        Summary { threshold: 1000, edge: 3 }
+    }
+}
+
+static SUMMARY_THRESHOLD: AtomicUsize = AtomicUsize::new(1000);
+static SUMMARY_EDGE: AtomicUsize = AtomicUsize::new(3);
+
+/// Returns the summarization used by specs that do not override it.
+pub fn summary() -> Summary {
+    Summary {
+        threshold: SUMMARY_THRESHOLD.load(Ordering::Relaxed),
+        edge: SUMMARY_EDGE.load(Ordering::Relaxed),
+    }
+}
+
+/// Sets the summarization used by specs that do not override it, such as
+/// `{v:a}`, unlike `{v:a<edge=5>}`.
+pub fn set_summary(summary: Summary) {
+    SUMMARY_THRESHOLD.store(summary.threshold, Ordering::Relaxed);
+    SUMMARY_EDGE.store(summary.edge, Ordering::Relaxed);
+}
+
+/// Formats `value` for the `:a` spec: nested sequences get one element per
+/// line with depth-adaptive indentation, long maps and structures are
+/// pretty-printed, anything else keeps its compact `Debug` form. With
+/// `sorted`, map keys and set elements are ordered at every depth.
+pub fn format_container<T: Debug + ?Sized>(value: &T, summary: Summary, sorted: bool) -> String {
+    let debug_str = format!("{:?}", value);
+    if debug_str.starts_with('[') {
+        // Tableaux et vecteurs
+        format_array(&debug_str, summary, sorted)
+    } else if debug_str.starts_with('{') && (debug_str.contains('\n') || debug_str.len() >= 100) {
+        // Maps et structures
+        format_pretty(value, summary, sorted)
+    } else {
+        render(&debug_str, summary, sorted, |node, result| node.write_compact(result))
+    }
+}
+
+/// Indents the `Debug` string of a sequence of any depth: a sequence holding
+/// other sequences gets one element per line, a sequence of scalars stays
+/// on a single line. Anything that is not a sequence is returned unchanged.
+pub fn format_array(debug_str: &str, summary: Summary, sorted: bool) -> String {
+    render(debug_str, summary, sorted, |node, result| node.write_array(0, result))
+}
+
+/// Formats `value` for the `:c` spec: compact `Debug`, with map keys and set
+/// elements ordered at every depth if `sorted` is set.
+pub fn format_compact<T: Debug + ?Sized>(value: &T, sorted: bool) -> String {
+    let debug_str = format!("{:?}", value);
+    if !sorted {
+        return debug_str;
+    }
+    let no_summary = Summary { threshold: usize::MAX, edge: 0 };
+    render(&debug_str, no_summary, sorted, |node, result| node.write_compact(result))
+}
+
+/// Formats `value` for the `:j` spec: pretty-printed `Debug`, with map keys
+/// and set elements ordered at every depth if `sorted` is set.
+pub fn format_pretty<T: Debug + ?Sized>(value: &T, summary: Summary, sorted: bool) -> String {
+    let debug_str = format!("{:?}", value);
+    let root = Node::parse(&debug_str);
+    if !sorted && !root.exceeds(summary.threshold) {
+        return format!("{:#?}", value);
+    }
+    render(&debug_str, summary, sorted, |node, result| node.write_pretty(0, result))
+}
+
+/// Parses `debug_str`, sorts and summarizes it, and renders it with `write`.
+fn render(debug_str: &str, summary: Summary, sorted: bool, write: impl Fn(&Node, &mut String)) -> String {
+    let mut root = Node::parse(debug_str);
+    if sorted {
+        root.sort();
+    }
+    root.summarize(summary.threshold, summary.edge);
+    let mut result = String::with_capacity(debug_str.len());
+    write(&root, &mut result);
+    result
+}
+
+/// Formats `value` for the `:m` spec: a rectangular 2D sequence is laid out
+/// with right-aligned columns, floats rounded to `precision` decimals if
+/// given, and with row and column index headers if `index` is set. Any
+/// other value is formatted as by `:a`.
+pub fn format_matrix<T: std::fmt::Debug + ?Sized>(value: &T, precision: Option<usize>, index: bool) -> String {
+    let debug_str = format!("{:?}", value);
+    let Some(rows) = matrix_cells(&Node::parse(&debug_str)) else {
+        return format_container(value, summary(), false);
+    };
+    if rows.is_empty() {
+        return debug_str;
+    }
+    let cells: Vec<Vec<String>> = rows
+        .iter()
+        .map(|row| row.iter().map(|cell| round_cell(cell, precision)).collect())
+        .collect();
+    let mut widths = vec![0; cells[0].len()];
+    for row in &cells {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    // Rows without columns have nothing to align.
+    if widths.is_empty() {
+        return format_container(value, summary(), false);
+    }
+    let mut result = String::new();
+    if index {
+        let label_width = (cells.len() - 1).to_string().len();
+        for (j, width) in widths.iter_mut().enumerate() {
+            *width = (*width).max(j.to_string().len());
+        }
+        result.push_str(&" ".repeat(label_width));
+        for (j, width) in widths.iter().enumerate() {
+            result.push_str(&format!("  {:>width$}", j, width = width));
+        }
+        for (i, row) in cells.iter().enumerate() {
+            result.push_str(&format!("\n{:<width$}", i, width = label_width));
+            for (cell, width) in row.iter().zip(&widths) {
+                result.push_str(&format!("  {:>width$}", cell, width = width));
+            }
+        }
+        return result;
+    }
+    for (i, row) in cells.iter().enumerate() {
+        result.push_str(if i == 0 { "[[" } else { " [" });
+        let aligned: Vec<String> = row
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{:>width$}", cell, width = width))
+            .collect();
+        result.push_str(&aligned.join(", "));
+        result.push_str(if i + 1 == cells.len() { "]]" } else { "],\n" });
+    }
+    result
+}
+
+/// Splits a rectangular 2D sequence of scalars into its rows of cells.
+fn matrix_cells(root: &Node) -> Option<Vec<Vec<String>>> {
+    let mut rows = Vec::new();
+    for row in &root.as_sequence()?.items {
+        let mut cells = Vec::new();
+        for cell in &row.as_sequence()?.items {
+            if cell.as_sequence().is_some() {
+                return None;
+            }
+            let mut text = String::new();
+            cell.write_compact(&mut text);
+            cells.push(text);
+        }
+        rows.push(cells);
+    }
+    let rectangular = rows.windows(2).all(|pair| pair[0].len() == pair[1].len());
+    rectangular.then_some(rows)
+}
+
+/// Rounds `cell` to `precision` decimals if it is a float.
+fn round_cell(cell: &str, precision: Option<usize>) -> String {
+    let is_float = cell.contains(['.', 'e', 'E']) || matches!(cell, "inf" | "-inf" | "NaN");
+    match (precision, cell.parse::<f64>()) {
+        (Some(precision), Ok(x)) if is_float => format!("{:.*}", precision, x),
+        _ => cell.to_string(),
+    }
+}
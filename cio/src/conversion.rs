@@ -0,0 +1,18 @@
+// conversion.rs
+//! Runtime support of the `!a` conversion flag of the formatting macros.
+
+use std::fmt::Debug;
+
+/// Formats `value` for the `!a` conversion: its `Debug` form, with every
+/// non-ASCII character escaped as `\u{..}`, like Python's `ascii()`.
+pub fn format_ascii<T: Debug + ?Sized>(value: &T) -> String {
+    let mut result = String::new();
+    for c in format!("{:?}", value).chars() {
+        if c.is_ascii() {
+            result.push(c);
+        } else {
+            result.extend(c.escape_unicode());
+        }
+    }
+    result
+}
@@ -0,0 +1,358 @@
+// debug_tree.rs
+//! Parsing of compact `Debug` strings back into their nested structure, and
+//! rendering of that structure in the layouts of the container specs.
+
+/// A value parsed from its compact `Debug` string.
+pub(crate) enum Node<'a> {
+    /// Anything without nested structure, kept verbatim.
+    Atom(&'a str),
+    /// A `key: value` map entry or struct field.
+    Entry(Box<Node<'a>>, Box<Node<'a>>),
+    /// A bracketed group of items: `[..]`, `{..}`, `Name { .. }`, `Some(..)`...
+    Group(Group<'a>),
+}
+
+pub(crate) struct Group<'a> {
+    /// Text before the opening bracket, like `Some` or `Person `.
+    pub(crate) prefix: &'a str,
+    pub(crate) open: char,
+    pub(crate) close: char,
+    /// Whether the items are padded with spaces, as in `Name { a: 1 }`.
+    pub(crate) padded: bool,
+    /// Whether the last item is followed by a comma, as in `(1,)`.
+    pub(crate) trailing_comma: bool,
+    pub(crate) items: Vec<Node<'a>>,
+    /// Number of items before summarization, if some were elided.
+    pub(crate) elided_from: Option<usize>,
+}
+
+impl<'a> Node<'a> {
+    pub(crate) fn parse(s: &'a str) -> Node<'a> {
+        let s = s.trim();
+        if let Some(colon) = top_level_colon(s) {
+            let key = Node::parse(&s[..colon]);
+            let value = Node::parse(&s[colon + 1..]);
+            return Node::Entry(Box::new(key), Box::new(value));
+        }
+        match Group::parse(s) {
+            Some(group) => Node::Group(group),
+            None => Node::Atom(s),
+        }
+    }
+
+    /// Returns the group if this node is a `[..]` sequence.
+    pub(crate) fn as_sequence(&self) -> Option<&Group<'a>> {
+        match self {
+            Node::Group(group) if group.prefix.is_empty() && group.open == '[' => Some(group),
+            _ => None,
+        }
+    }
+
+    /// Returns whether this node or any node below it has more than
+    /// `threshold` items.
+    pub(crate) fn exceeds(&self, threshold: usize) -> bool {
+        match self {
+            Node::Atom(_) => false,
+            Node::Entry(key, value) => key.exceeds(threshold) || value.exceeds(threshold),
+            Node::Group(group) => {
+                group.items.len() > threshold || group.items.iter().any(|item| item.exceeds(threshold))
+            }
+        }
+    }
+
+    /// Orders the items of every map and set by their compact `Debug` form,
+    /// or by their key for map entries, comparing numbers by value and
+    /// placing them before the other keys.
+    pub(crate) fn sort(&mut self) {
+        match self {
+            Node::Atom(_) => {}
+            Node::Entry(key, value) => {
+                key.sort();
+                value.sort();
+            }
+            Node::Group(group) => {
+                for item in &mut group.items {
+                    item.sort();
+                }
+                if group.prefix.is_empty() && group.open == '{' {
+                    sort_by_text(&mut group.items, Node::sort_key);
+                }
+            }
+        }
+    }
+
+    fn sort_key(&self) -> String {
+        let mut key = String::new();
+        match self {
+            Node::Entry(entry_key, _) => entry_key.write_compact(&mut key),
+            node => node.write_compact(&mut key),
+        }
+        key
+    }
+
+    /// Replaces the middle items of every group with more than `threshold`
+    /// items by a `...` item, keeping `edge` items at each end.
+    pub(crate) fn summarize(&mut self, threshold: usize, edge: usize) {
+        match self {
+            Node::Atom(_) => {}
+            Node::Entry(key, value) => {
+                key.summarize(threshold, edge);
+                value.summarize(threshold, edge);
+            }
+            Node::Group(group) => {
+                let len = group.items.len();
+                if len > threshold && len > 2 * edge {
+                    group.items.drain(edge..len - edge);
+                    group.items.insert(edge, Node::Atom("..."));
+                    group.trailing_comma = false;
+                    group.elided_from = Some(len);
+                }
+                for item in &mut group.items {
+                    item.summarize(threshold, edge);
+                }
+            }
+        }
+    }
+
+    /// Writes the compact single-line form, as printed by `{:?}`.
+    pub(crate) fn write_compact(&self, result: &mut String) {
+        match self {
+            Node::Atom(s) => result.push_str(s),
+            Node::Entry(key, value) => {
+                key.write_compact(result);
+                result.push_str(": ");
+                value.write_compact(result);
+            }
+            Node::Group(group) => {
+                result.push_str(group.prefix);
+                result.push(group.open);
+                if group.padded {
+                    result.push(' ');
+                }
+                for (i, item) in group.items.iter().enumerate() {
+                    if i > 0 {
+                        result.push_str(", ");
+                    }
+                    item.write_compact(result);
+                }
+                if group.trailing_comma {
+                    result.push(',');
+                }
+                if group.padded {
+                    result.push(' ');
+                }
+                result.push(group.close);
+                group.write_len(result);
+            }
+        }
+    }
+
+    /// Writes the multi-line form, as printed by `{:#?}`.
+    pub(crate) fn write_pretty(&self, level: usize, result: &mut String) {
+        match self {
+            Node::Atom(s) => result.push_str(s),
+            Node::Entry(key, value) => {
+                key.write_pretty(level, result);
+                result.push_str(": ");
+                value.write_pretty(level, result);
+            }
+            Node::Group(group) if group.items.is_empty() => self.write_compact(result),
+            Node::Group(group) => {
+                result.push_str(group.prefix);
+                result.push(group.open);
+                for item in &group.items {
+                    result.push('\n');
+                    indent(level + 1, result);
+                    item.write_pretty(level + 1, result);
+                    result.push(',');
+                }
+                result.push('\n');
+                indent(level, result);
+                result.push(group.close);
+                group.write_len(result);
+            }
+        }
+    }
+
+    /// Writes the `:a` form: a sequence holding other sequences gets one
+    /// item per line, anything else is written compactly.
+    pub(crate) fn write_array(&self, level: usize, result: &mut String) {
+        let group = match self.as_sequence() {
+            Some(group) if group.items.iter().any(|item| item.as_sequence().is_some()) => group,
+            _ => return self.write_compact(result),
+        };
+        result.push('[');
+        for (i, item) in group.items.iter().enumerate() {
+            if i > 0 {
+                result.push(',');
+            }
+            result.push('\n');
+            indent(level + 1, result);
+            item.write_array(level + 1, result);
+        }
+        result.push('\n');
+        indent(level, result);
+        result.push(']');
+        group.write_len(result);
+    }
+}
+
+impl<'a> Group<'a> {
+    /// Parses `s` if it is a prefix followed by a single bracketed group.
+    fn parse(s: &'a str) -> Option<Group<'a>> {
+        let close = s.chars().last()?;
+        if !matches!(close, ']' | ')' | '}') {
+            return None;
+        }
+        let mut punctuation = Punctuation::new(s);
+        let (open_at, open) = punctuation.next()?;
+        if !matches!(open, b'[' | b'(' | b'{') {
+            return None;
+        }
+        let mut depth = 1;
+        let mut start = open_at + 1;
+        let mut items = Vec::new();
+        let mut trailing_comma = false;
+        for (i, b) in punctuation {
+            match b {
+                b'[' | b'(' | b'{' => depth += 1,
+                b']' | b')' | b'}' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        if i + 1 != s.len() {
+                            return None;
+                        }
+                        let last = s[start..i].trim();
+                        if last.is_empty() {
+                            trailing_comma = !items.is_empty();
+                        } else {
+                            items.push(Node::parse(last));
+                        }
+                        return Some(Group {
+                            prefix: &s[..open_at],
+                            open: open as char,
+                            close,
+                            padded: s[open_at + 1..].starts_with(' '),
+                            trailing_comma,
+                            items,
+                            elided_from: None,
+                        });
+                    }
+                }
+                b',' if depth == 1 => {
+                    items.push(Node::parse(&s[start..i]));
+                    start = i + 1;
+                }
+                _ => {}
+            }
+        }
+        None
+    }
+
+    fn write_len(&self, result: &mut String) {
+        if let Some(len) = self.elided_from {
+            result.push_str(&format!(" ({} items)", len));
+        }
+    }
+}
+
+/// Sorts `items` by the text of their `key`, comparing numbers by value and
+/// placing them before the other keys, such as identifiers, so that the
+/// order is total when keys of both kinds mix.
+pub(crate) fn sort_by_text<T>(items: &mut Vec<T>, key: impl Fn(&T) -> String) {
+    let mut keyed: Vec<(Option<f64>, String, T)> = items
+        .drain(..)
+        .map(|item| {
+            let text = key(&item);
+            (text.parse().ok(), text, item)
+        })
+        .collect();
+    keyed.sort_by(|(a_number, a, _), (b_number, b, _)| {
+        b_number.is_some().cmp(&a_number.is_some()).then_with(|| match (a_number, b_number) {
+            (Some(a_number), Some(b_number)) => a_number.total_cmp(b_number).then_with(|| a.cmp(b)),
+            _ => a.cmp(b),
+        })
+    });
+    items.extend(keyed.into_iter().map(|(_, _, item)| item));
+}
+
+fn indent(level: usize, result: &mut String) {
+    result.push_str(&"    ".repeat(level));
+}
+
+/// Returns the offset of the `: ` separating a map key or field name from
+/// its value, if `s` is such an entry.
+fn top_level_colon(s: &str) -> Option<usize> {
+    let mut depth = 0isize;
+    for (i, b) in Punctuation::new(s) {
+        match b {
+            b'[' | b'(' | b'{' => depth += 1,
+            b']' | b')' | b'}' => depth -= 1,
+            b':' if depth == 0 && s[i + 1..].starts_with(' ') => return Some(i),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Iterates over the brackets, commas and colons of a `Debug` string, with
+/// their byte offsets, skipping those inside string and char literals.
+struct Punctuation<'a> {
+    s: &'a str,
+    pos: usize,
+}
+
+impl<'a> Punctuation<'a> {
+    fn new(s: &'a str) -> Self {
+        Punctuation { s, pos: 0 }
+    }
+}
+
+impl Iterator for Punctuation<'_> {
+    type Item = (usize, u8);
+
+    fn next(&mut self) -> Option<(usize, u8)> {
+        let bytes = self.s.as_bytes();
+        while self.pos < bytes.len() {
+            let i = self.pos;
+            match bytes[i] {
+                b'"' | b'\'' => self.pos += literal_len(&self.s[i..]).unwrap_or(1),
+                b @ (b'[' | b'(' | b'{' | b']' | b')' | b'}' | b',' | b':') => {
+                    self.pos += 1;
+                    return Some((i, b));
+                }
+                _ => self.pos += 1,
+            }
+        }
+        None
+    }
+}
+
+/// Returns the byte length of the string or char literal `s` starts with,
+/// honouring backslash escapes.
+fn literal_len(s: &str) -> Option<usize> {
+    let mut chars = s.char_indices();
+    let (_, quote) = chars.next()?;
+    if quote == '"' {
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '\\' => {
+                    chars.next();
+                }
+                '"' => return Some(i + 1),
+                _ => {}
+            }
+        }
+        return None;
+    }
+    match chars.next()? {
+        (_, '\\') => {
+            chars.next()?;
+            chars.find(|&(_, c)| c == '\'').map(|(i, _)| i + 1)
+        }
+        _ => match chars.next()? {
+            (i, '\'') => Some(i + 1),
+            _ => None,
+        },
+    }
+}
@@ -0,0 +1,63 @@
+// json.rs
+//! Runtime support of the `:json` and `:json#` specs, which need the `serde`
+//! feature.
+
+#[cfg(feature = "serde")]
+pub use serde::Serialize as Json;
+
+/// Stands in for `serde::Serialize` when the `serde` feature is disabled, so
+/// that `:json` fails with a hint instead of an unresolved path. It is
+/// sealed: no type can implement it, so `:json` never compiles without the
+/// feature.
+#[cfg(not(feature = "serde"))]
+#[diagnostic::on_unimplemented(
+    message = "`:json` needs the `serde` feature of `cio`",
+    label = "cannot be printed as JSON",
+    note = "enable it with `cio = {{ version = \"0.1.0\", features = [\"serde\"] }}`"
+)]
+pub trait Json: sealed::Sealed {}
+
+#[cfg(not(feature = "serde"))]
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// Formats `value` as JSON, compact for `:json` or indented for `:json#`,
+/// with object keys ordered at every depth if `sorted` is set. Values that
+/// cannot be represented in JSON, like maps with non-string keys, are
+/// replaced by a description of the error.
+#[cfg(feature = "serde")]
+pub fn format_json<T: Json + ?Sized>(value: &T, pretty: bool, sorted: bool) -> String {
+    let json = if sorted {
+        serde_json::to_value(value).map(sort_keys).and_then(|value| match pretty {
+            true => serde_json::to_string_pretty(&value),
+            false => serde_json::to_string(&value),
+        })
+    } else if pretty {
+        serde_json::to_string_pretty(value)
+    } else {
+        serde_json::to_string(value)
+    };
+    json.unwrap_or_else(|e| format!("<not JSON: {}>", e))
+}
+
+/// Rebuilds every object of `value` with its keys in order, whether or not
+/// `serde_json` preserves insertion order.
+#[cfg(feature = "serde")]
+fn sort_keys(value: serde_json::Value) -> serde_json::Value {
+    use serde_json::Value;
+    match value {
+        Value::Array(items) => Value::Array(items.into_iter().map(sort_keys).collect()),
+        Value::Object(map) => {
+            let mut entries: Vec<_> = map.into_iter().collect();
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+            Value::Object(entries.into_iter().map(|(k, v)| (k, sort_keys(v))).collect())
+        }
+        value => value,
+    }
+}
+
+#[cfg(not(feature = "serde"))]
+pub fn format_json<T: Json + ?Sized>(_value: &T, _pretty: bool, _sorted: bool) -> String {
+    unreachable!("`Json` has no implementations without the `serde` feature")
+}
@@ -0,0 +1,411 @@
+// number.rs
+//! Runtime support of the Python format specs that `std::fmt` cannot express,
+//! like `{n:,}`, `{ratio:.1%}`, `{x: }` or `{x:=+10}`.
+
+/// A Python format spec, `[[fill]align][sign][z][#][0][width][grouping][.precision][type]`,
+/// with the defaults of the `0` flag already applied to `fill` and `align`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NumberSpec {
+    pub fill: char,
+    pub align: Align,
+    pub sign: Sign,
+    /// `z`: negative zero, after rounding, is written as positive zero.
+    pub coerce_zero: bool,
+    /// `#`: `0b`/`0o`/`0x` prefixes, and a decimal point that is always kept.
+    pub alternate: bool,
+    pub width: usize,
+    /// `,` or `_`, inserted every three decimal digits or four binary,
+    /// octal or hexadecimal digits.
+    pub grouping: Option<char>,
+    pub precision: Option<usize>,
+    /// The presentation type, `None` for Python's default one.
+    pub kind: Option<Kind>,
+}
+
+impl Default for NumberSpec {
+    fn default() -> Self {
+        NumberSpec {
+            fill: ' ',
+            align: Align::Right,
+            sign: Sign::Minus,
+            coerce_zero: false,
+            alternate: false,
+            width: 0,
+            grouping: None,
+            precision: None,
+            kind: None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Align {
+    /// `<`
+    Left,
+    /// `>`
+    Right,
+    /// `^`
+    Center,
+    /// `=`: padding between the sign and the digits.
+    AfterSign,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Sign {
+    /// `-`: a sign for negative numbers only.
+    Minus,
+    /// `+`: a sign for all numbers.
+    Plus,
+    /// ` `: a space in place of the sign of positive numbers.
+    Space,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Kind {
+    /// `d`
+    Decimal,
+    /// `b`
+    Binary,
+    /// `o`
+    Octal,
+    /// `x`
+    Hex,
+    /// `X`
+    UpperHex,
+    /// `c`: the character with this code point.
+    Char,
+    /// `e`
+    Exponent,
+    /// `E`
+    UpperExponent,
+    /// `f`
+    Fixed,
+    /// `F`
+    UpperFixed,
+    /// `g`: fixed or exponent notation depending on the magnitude.
+    General,
+    /// `G`
+    UpperGeneral,
+    /// `%`: fixed notation of the value times 100, followed by `%`.
+    Percent,
+}
+
+/// A number as seen by Python's formatting: an exact integer or a float.
+#[derive(Clone, Copy, Debug)]
+pub enum Value {
+    Int { negative: bool, magnitude: u128 },
+    Float(f64),
+}
+
+/// Numbers accepted by the Python format specs.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` cannot be formatted with a Python number spec",
+    label = "not an integer or a float",
+    note = "specs like `,`, `_`, `%`, `=` or ` ` only apply to numbers"
+)]
+pub trait Number {
+    fn to_value(&self) -> Value;
+}
+
+/// Integers, the only numbers accepted by the `d`, `b`, `o`, `x`, `X` and
+/// `c` presentation types.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` cannot be formatted with an integer presentation type",
+    label = "not an integer",
+    note = "`d`, `b`, `o`, `x`, `X` and `c` only apply to integers"
+)]
+pub trait Integer: Number {}
+
+macro_rules! impl_integer {
+    ($($int:ty),*) => {$(
+        impl Number for $int {
+            fn to_value(&self) -> Value {
+                let value = *self as i128;
+                Value::Int { negative: value < 0, magnitude: value.unsigned_abs() }
+            }
+        }
+
+        impl Integer for $int {}
+    )*};
+}
+
+impl_integer!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+impl Number for i128 {
+    fn to_value(&self) -> Value {
+        Value::Int { negative: *self < 0, magnitude: self.unsigned_abs() }
+    }
+}
+
+impl Integer for i128 {}
+
+impl Number for u128 {
+    fn to_value(&self) -> Value {
+        Value::Int { negative: false, magnitude: *self }
+    }
+}
+
+impl Integer for u128 {}
+
+impl Number for f32 {
+    /// Goes through the shortest decimal form of the `f32`, so that `0.1f32`
+    /// is formatted as `0.1` rather than as `0.10000000149011612`.
+    fn to_value(&self) -> Value {
+        Value::Float(self.to_string().parse().unwrap_or(*self as f64))
+    }
+}
+
+impl Number for f64 {
+    fn to_value(&self) -> Value {
+        Value::Float(*self)
+    }
+}
+
+impl<T: Number + ?Sized> Number for &T {
+    fn to_value(&self) -> Value {
+        (**self).to_value()
+    }
+}
+
+impl<T: Integer + ?Sized> Integer for &T {}
+
+/// Formats `value` according to `spec` like Python's `format()` does.
+pub fn format_number<T: Number + ?Sized>(value: &T, spec: NumberSpec) -> String {
+    let mut parts = match value.to_value() {
+        Value::Int { negative, magnitude } => match spec.kind {
+            None | Some(Kind::Decimal) => Parts::digits(negative, magnitude.to_string()),
+            Some(Kind::Binary) => Parts::radix(negative, format!("{:b}", magnitude), "0b", &spec),
+            Some(Kind::Octal) => Parts::radix(negative, format!("{:o}", magnitude), "0o", &spec),
+            Some(Kind::Hex) => Parts::radix(negative, format!("{:x}", magnitude), "0x", &spec),
+            Some(Kind::UpperHex) => Parts::radix(negative, format!("{:X}", magnitude), "0X", &spec),
+            Some(Kind::Char) => {
+                let c = u32::try_from(magnitude).ok().and_then(char::from_u32);
+                let c = if negative { None } else { c };
+                Parts { rest: c.unwrap_or(char::REPLACEMENT_CHARACTER).to_string(), ..Parts::default() }
+            }
+            Some(_) => {
+                let float = magnitude as f64;
+                Parts::float(if negative { -float } else { float }, &spec)
+            }
+        },
+        Value::Float(float) => Parts::float(float, &spec),
+    };
+    if spec.coerce_zero && parts.is_zero() {
+        parts.negative = false;
+    }
+    parts.render(&spec)
+}
+
+/// Formats `value` with one of the integer presentation types.
+pub fn format_integer<T: Integer + ?Sized>(value: &T, spec: NumberSpec) -> String {
+    format_number(value, spec)
+}
+
+/// A formatted number before padding: the sign, the radix prefix, the
+/// integer digits subject to grouping and zero padding, and the rest.
+#[derive(Default)]
+struct Parts {
+    negative: bool,
+    prefix: &'static str,
+    digits: String,
+    /// Digits per group, 3 for decimal and 4 for other radixes.
+    group_size: usize,
+    rest: String,
+}
+
+impl Parts {
+    fn digits(negative: bool, digits: String) -> Parts {
+        Parts { negative, digits, group_size: 3, ..Parts::default() }
+    }
+
+    fn radix(negative: bool, digits: String, prefix: &'static str, spec: &NumberSpec) -> Parts {
+        let prefix = if spec.alternate { prefix } else { "" };
+        Parts { negative, prefix, digits, group_size: 4, ..Parts::default() }
+    }
+
+    fn float(value: f64, spec: &NumberSpec) -> Parts {
+        let upper = matches!(spec.kind, Some(Kind::UpperExponent | Kind::UpperFixed | Kind::UpperGeneral));
+        let percent = spec.kind == Some(Kind::Percent);
+        if !value.is_finite() {
+            let text = if value.is_nan() { "nan" } else { "inf" };
+            let text = if upper { text.to_uppercase() } else { text.to_string() };
+            let rest = if percent { text + "%" } else { text };
+            return Parts { negative: value < 0.0, rest, ..Parts::default() };
+        }
+        let negative = value.is_sign_negative();
+        let value = value.abs();
+        let mut text = match spec.kind {
+            Some(Kind::Exponent | Kind::UpperExponent) => exponent(value, spec.precision.unwrap_or(6), spec.alternate),
+            Some(Kind::Percent) => fixed(value * 100.0, spec.precision.unwrap_or(6), spec.alternate) + "%",
+            Some(Kind::General | Kind::UpperGeneral) => general(value, spec.precision.unwrap_or(6), spec.alternate, false),
+            None => match spec.precision {
+                Some(precision) => general(value, precision, spec.alternate, true),
+                None => shortest(value),
+            },
+            _ => fixed(value, spec.precision.unwrap_or(6), spec.alternate),
+        };
+        if upper {
+            text = text.to_uppercase();
+        }
+        let split = text.find(|c: char| !c.is_ascii_digit()).unwrap_or(text.len());
+        let rest = text.split_off(split);
+        Parts { rest, ..Parts::digits(negative, text) }
+    }
+
+    fn is_zero(&self) -> bool {
+        self.digits.chars().chain(self.rest.chars()).take_while(|c| !matches!(c, 'e' | 'E')).all(|c| matches!(c, '0' | '.' | '%'))
+    }
+
+    fn render(self, spec: &NumberSpec) -> String {
+        let sign = match (self.negative, spec.sign) {
+            (true, _) => "-",
+            (false, Sign::Minus) => "",
+            (false, Sign::Plus) => "+",
+            (false, Sign::Space) => " ",
+        };
+        let grouping = spec.grouping.filter(|_| !self.digits.is_empty());
+        let mut digits = self.digits;
+        let fixed_len = sign.len() + self.prefix.len() + self.rest.chars().count();
+        if spec.fill == '0' && spec.align == Align::AfterSign && !digits.is_empty() {
+            // Zero padding goes between the digits, so it is grouped too.
+            while fixed_len + group(&digits, grouping, self.group_size).len() < spec.width {
+                digits.insert(0, '0');
+            }
+        }
+        let body = group(&digits, grouping, self.group_size) + &self.rest;
+        let len = sign.len() + self.prefix.len() + body.chars().count();
+        let padding = spec.width.saturating_sub(len);
+        let fill = |count: usize| spec.fill.to_string().repeat(count);
+        match spec.align {
+            Align::Left => format!("{}{}{}{}", sign, self.prefix, body, fill(padding)),
+            Align::Right => format!("{}{}{}{}", fill(padding), sign, self.prefix, body),
+            Align::Center => {
+                let left = padding / 2;
+                format!("{}{}{}{}{}", fill(left), sign, self.prefix, body, fill(padding - left))
+            }
+            Align::AfterSign => format!("{}{}{}{}", sign, self.prefix, fill(padding), body),
+        }
+    }
+}
+
+/// Inserts `separator` every `size` digits, from the right.
+fn group(digits: &str, separator: Option<char>, size: usize) -> String {
+    let Some(separator) = separator else {
+        return digits.to_string();
+    };
+    let mut result = String::new();
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(size) {
+            result.push(separator);
+        }
+        result.push(c);
+    }
+    result
+}
+
+/// `f`: fixed notation with `precision` decimals.
+fn fixed(value: f64, precision: usize, alternate: bool) -> String {
+    let mut text = format!("{:.*}", precision, value);
+    if alternate && precision == 0 {
+        text.push('.');
+    }
+    text
+}
+
+/// `e`: exponent notation with `precision` decimals and an exponent of at
+/// least two digits, as in `1.500000e+03`.
+fn exponent(value: f64, precision: usize, alternate: bool) -> String {
+    let text = format!("{:.*e}", precision, value);
+    let (mantissa, exp) = text.split_once('e').unwrap_or((&text, "0"));
+    let dot = if alternate && precision == 0 { "." } else { "" };
+    format!("{}{}e{:+03}", mantissa, dot, exp.parse::<i32>().unwrap_or(0))
+}
+
+/// `g`, or no type with a precision if `add_dot_zero` is set: `precision`
+/// significant digits in fixed notation for moderate exponents and in
+/// exponent notation otherwise, without trailing zeros unless `alternate`.
+fn general(value: f64, precision: usize, alternate: bool, add_dot_zero: bool) -> String {
+    let precision = precision.max(1);
+    let exp = decimal_exponent(&format!("{:.*e}", precision - 1, value));
+    let limit = if add_dot_zero { precision as i32 - 1 } else { precision as i32 };
+    if value != 0.0 && (exp < -4 || exp >= limit) {
+        let text = exponent(value, precision - 1, alternate);
+        return match text.split_once('e') {
+            Some((mantissa, exp)) if !alternate => format!("{}e{}", trim_zeros(mantissa), exp),
+            _ => text,
+        };
+    }
+    let decimals = (precision as i32 - 1 - exp).max(0) as usize;
+    let text = fixed(value, decimals, alternate);
+    if alternate {
+        return text;
+    }
+    let text = trim_zeros(&text).to_string();
+    if add_dot_zero && !text.contains('.') {
+        return text + ".0";
+    }
+    text
+}
+
+/// No type nor precision: the shortest representation that reads back as
+/// the same float, in exponent notation for very large or small values.
+fn shortest(value: f64) -> String {
+    let exp = decimal_exponent(&format!("{:e}", value));
+    if value != 0.0 && !(-4..16).contains(&exp) {
+        let text = format!("{:e}", value);
+        let (mantissa, exp) = text.split_once('e').unwrap_or((&text, "0"));
+        return format!("{}e{:+03}", mantissa, exp.parse::<i32>().unwrap_or(0));
+    }
+    let text = value.to_string();
+    if text.contains('.') {
+        text
+    } else {
+        text + ".0"
+    }
+}
+
+fn decimal_exponent(text: &str) -> i32 {
+    text.split_once('e').and_then(|(_, exp)| exp.parse().ok()).unwrap_or(0)
+}
+
+fn trim_zeros(text: &str) -> &str {
+    if text.contains('.') {
+        text.trim_end_matches('0').trim_end_matches('.')
+    } else {
+        text
+    }
+}
+
+impl Align {
+    /// Converts a dynamic alignment, like the `{align}` of `{x:{align}10}`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `c` is not one of `<`, `>`, `^` and `=`.
+    pub fn from_char(c: char) -> Align {
+        match c {
+            '<' => Align::Left,
+            '>' => Align::Right,
+            '^' => Align::Center,
+            '=' => Align::AfterSign,
+            _ => panic!("invalid alignment `{}`; expected `<`, `>`, `^` or `=`", c),
+        }
+    }
+}
+
+/// Pads `text` to `width` characters with a dynamic fill or alignment,
+/// which `std::fmt` cannot express. With `Align::AfterSign`, the padding
+/// goes after a leading sign.
+pub fn pad(text: &str, fill: char, align: Align, width: usize) -> String {
+    let padding = width.saturating_sub(text.chars().count());
+    let fill = |count: usize| fill.to_string().repeat(count);
+    match align {
+        Align::Left => format!("{}{}", text, fill(padding)),
+        Align::Right => format!("{}{}", fill(padding), text),
+        Align::Center => format!("{}{}{}", fill(padding / 2), text, fill(padding - padding / 2)),
+        Align::AfterSign => {
+            let sign_len = if text.starts_with(['+', '-', ' ']) { 1 } else { 0 };
+            format!("{}{}{}", &text[..sign_len], fill(padding), &text[sign_len..])
+        }
+    }
+}
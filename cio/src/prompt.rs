@@ -0,0 +1,238 @@
+// prompt.rs
+//! Runtime support of the `input!` and `try_input!` macros.
+
+use std::fmt::{self, Display};
+use std::io::{self, Write};
+use std::str::FromStr;
+
+/// Why `try_input!` returned without a value. `E` is the `FromStr` error of
+/// the type read, or `TokenError` for tuples and vectors.
+#[derive(Debug)]
+pub enum InputError<E> {
+    /// Standard input was closed before a line was read.
+    Eof,
+    /// Reading standard input or writing the prompt failed.
+    Io(io::Error),
+    /// The last line allowed was empty.
+    Empty,
+    /// The last line allowed failed to parse.
+    Parse(E),
+    /// The last value allowed failed the `validate`, `range`, `matches` or
+    /// `one_of` check, with its message.
+    Invalid(String),
+}
+
+impl<E: Display> Display for InputError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputError::Eof => f.write_str("end of input: standard input was closed"),
+            InputError::Io(e) => write!(f, "failed to read standard input: {}", e),
+            InputError::Empty => f.write_str("Unauthorized empty input"),
+            InputError::Parse(e) => e.fmt(f),
+            InputError::Invalid(message) => f.write_str(message),
+        }
+    }
+}
+
+impl<E: std::error::Error + 'static> std::error::Error for InputError<E> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            InputError::Io(e) => Some(e),
+            InputError::Parse(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl<E> From<io::Error> for InputError<E> {
+    fn from(e: io::Error) -> Self {
+        InputError::Io(e)
+    }
+}
+
+/// How many times `try_input!` prompts again after an invalid line, unless
+/// given `retries = n`.
+pub const RETRIES: usize = 3;
+
+/// Prints `prompt` and reads one line of standard input, without its
+/// surrounding whitespace. A closed standard input is `InputError::Eof`
+/// rather than an empty line.
+pub fn read_line<E>(prompt: &str) -> Result<String, InputError<E>> {
+    let mut stdout = io::stdout();
+    stdout.write_all(prompt.as_bytes())?;
+    stdout.flush()?;
+    let mut line = String::new();
+    if io::stdin().read_line(&mut line)? == 0 {
+        return Err(InputError::Eof);
+    }
+    Ok(line.trim().to_string())
+}
+
+/// Reads lines at `prompt` until `parse` accepts one, printing the error and
+/// prompting again after each rejected line, at most `retries` times if
+/// given.
+pub fn read<T, E: Display>(
+    prompt: &str,
+    retries: Option<usize>,
+    mut parse: impl FnMut(&str) -> Result<T, InputError<E>>,
+) -> Result<T, InputError<E>> {
+    let mut attempts = 0;
+    loop {
+        let line = read_line(prompt)?;
+        let error = match parse(&line) {
+            Ok(value) => return Ok(value),
+            Err(error) => error,
+        };
+        println!("Error: {}.", error);
+        if retries.is_some_and(|retries| attempts >= retries) {
+            return Err(error);
+        }
+        attempts += 1;
+    }
+}
+
+/// Why a line of several values failed to parse.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TokenError {
+    /// The line has `found` values where the tuple has `expected` fields.
+    Count { expected: usize, found: usize },
+    /// The token at `index`, from 0, failed to parse, with the message of
+    /// the `FromStr` error of its type.
+    Parse { index: usize, token: String, message: String },
+}
+
+impl Display for TokenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TokenError::Count { expected, found } => write!(f, "expected {} values, found {}", expected, found),
+            TokenError::Parse { index, token, message } => write!(f, "value {} (`{}`): {}", index + 1, token, message),
+        }
+    }
+}
+
+impl std::error::Error for TokenError {}
+
+/// The `FromLine` implementation of `FromStr` types, parsing the whole line.
+pub enum Scalar {}
+
+/// The `FromLine` implementations of tuples and vectors, parsing each token
+/// of the line.
+pub enum Tokens {}
+
+/// A type read from a line by `input!`. `Marker` is `Scalar` for `FromStr`
+/// types and `Tokens` for tuples and `Vec`, and is inferred, so that both
+/// can be read without the overlap of a single blanket implementation.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` cannot be read by `input!`",
+    label = "not parsable from a line",
+    note = "`input!` reads `FromStr` types, and tuples and `Vec` of them"
+)]
+pub trait FromLine<Marker>: Sized {
+    type Err;
+
+    /// Parses a non-empty line, whose tokens are separated by `sep` or else
+    /// by whitespace.
+    fn from_line(line: &str, sep: Option<&str>) -> Result<Self, Self::Err>;
+}
+
+impl<T: FromStr> FromLine<Scalar> for T {
+    type Err = T::Err;
+
+    fn from_line(line: &str, _sep: Option<&str>) -> Result<Self, Self::Err> {
+        line.parse()
+    }
+}
+
+/// The tokens of `line`, trimmed.
+fn split<'a>(line: &'a str, sep: Option<&'a str>) -> Vec<&'a str> {
+    match sep {
+        Some(sep) => line.split(sep).map(str::trim).collect(),
+        None => line.split_whitespace().collect(),
+    }
+}
+
+/// Parses the token at `index`.
+fn token<T: FromStr>(index: usize, token: &str) -> Result<T, TokenError>
+where
+    T::Err: Display,
+{
+    token.parse().map_err(|e: T::Err| TokenError::Parse { index, token: token.to_string(), message: e.to_string() })
+}
+
+impl<T: FromStr> FromLine<Tokens> for Vec<T>
+where
+    T::Err: Display,
+{
+    type Err = TokenError;
+
+    fn from_line(line: &str, sep: Option<&str>) -> Result<Self, Self::Err> {
+        split(line, sep).into_iter().enumerate().map(|(index, text)| token(index, text)).collect()
+    }
+}
+
+macro_rules! tuple_lines {
+    ($($len:literal ($($name:ident $index:tt),+))*) => {$(
+        impl<$($name: FromStr),+> FromLine<Tokens> for ($($name,)+)
+        where
+            $($name::Err: Display),+
+        {
+            type Err = TokenError;
+
+            fn from_line(line: &str, sep: Option<&str>) -> Result<Self, Self::Err> {
+                let tokens = split(line, sep);
+                if tokens.len() != $len {
+                    return Err(TokenError::Count { expected: $len, found: tokens.len() });
+                }
+                Ok(($(token::<$name>($index, tokens[$index])?,)+))
+            }
+        }
+    )*};
+}
+
+tuple_lines! {
+    1 (A 0)
+    2 (A 0, B 1)
+    3 (A 0, B 1, C 2)
+    4 (A 0, B 1, C 2, D 3)
+    5 (A 0, B 1, C 2, D 3, E 4)
+    6 (A 0, B 1, C 2, D 3, E 4, F 5)
+    7 (A 0, B 1, C 2, D 3, E 4, F 5, G 6)
+    8 (A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7)
+}
+
+/// Parses a non-empty line as a `T`: a `FromStr` type, or a tuple or `Vec`
+/// of whitespace-separated values.
+pub fn parse<T: FromLine<M>, M>(line: &str) -> Result<T, InputError<T::Err>> {
+    parse_separated(line, None)
+}
+
+/// Parses a non-empty line as a `T`, with the values of tuples and vectors
+/// separated by `sep` if given.
+pub fn parse_separated<T: FromLine<M>, M>(line: &str, sep: Option<&str>) -> Result<T, InputError<T::Err>> {
+    if line.is_empty() {
+        return Err(InputError::Empty);
+    }
+    T::from_line(line, sep).map_err(InputError::Parse)
+}
+
+/// Compiles the regular expression of a `matches` option, once before the
+/// first prompt.
+pub fn pattern(pattern: &str) -> regex::Regex {
+    regex::Regex::new(pattern).expect("the pattern is checked when the macro expands")
+}
+
+/// The prompt of an input with a `default`: `Port [8080]: ` for the prompt
+/// `Port` or `Port: `.
+pub fn with_default(prompt: &str, default: &dyn Display) -> String {
+    let name = prompt.trim_end().trim_end_matches(':').trim_end();
+    format!("{} [{}]: ", name, default)
+}
+
+/// Ends the program after `input!` failed to read a value, with the reason
+/// on standard error and a failure exit code.
+pub fn exit<E: Display>(prompt: &str, error: InputError<E>) -> ! {
+    // The prompt is still on the current line.
+    println!();
+    eprintln!("Error: {} while waiting for {:?}.", error, prompt.trim());
+    std::process::exit(1)
+}
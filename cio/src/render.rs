@@ -0,0 +1,53 @@
+// render.rs
+//! How `#[derive(CioDisplay)]` structs render in the container specs: their
+//! `Debug` form, read by `:a`, `:c` and `:j`, and their table rows for `:t`
+//! follow the `#[cio(...)]` attributes of their fields.
+
+use std::fmt::{self, Debug, Formatter};
+
+pub use cio_macros::CioDisplay;
+
+/// The fields of a struct as the container specs show them, implemented by
+/// `#[derive(CioDisplay)]`. The derive also implements `Debug` and
+/// `cio::table::Row` from it, so it replaces `#[derive(Debug)]`:
+///
+/// - `#[cio(skip)]` hides a field,
+/// - `#[cio(rename = "...")]` shows it under another name,
+/// - `#[cio(spec = "...")]` formats it with a placeholder spec, like `.2`,
+///   `>8` or `,`, instead of `Debug`.
+///
+/// Fields show in declaration order.
+pub trait CioDisplay {
+    /// The name before the fields, as in `Person { .. }`.
+    const NAME: &'static str;
+
+    /// The shown fields, with their shown names.
+    fn fields(&self) -> Vec<(&'static str, Value<'_>)>;
+}
+
+/// The value of a shown field.
+pub enum Value<'a> {
+    /// A field shown with its own `Debug`.
+    Debug(&'a dyn Debug),
+    /// A field formatted with its spec.
+    Text(String),
+}
+
+impl Debug for Value<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Debug(value) => value.fmt(f),
+            Value::Text(text) => f.write_str(text),
+        }
+    }
+}
+
+/// Writes the `Debug` form of `value`, `Name { field: value, .. }`, with the
+/// pretty-printed layout for `{:#?}`.
+pub fn debug_fields<T: CioDisplay + ?Sized>(value: &T, f: &mut Formatter<'_>) -> fmt::Result {
+    let mut debug = f.debug_struct(T::NAME);
+    for (name, field) in value.fields() {
+        debug.field(name, &field);
+    }
+    debug.finish()
+}
@@ -0,0 +1,79 @@
+// style.rs
+//! Runtime switch for the ANSI escapes of the `[bold red]...[/]` markup and
+//! the `{x:green}` style specs of the formatting macros.
+
+use std::io::IsTerminal;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::OnceLock;
+
+/// Whether the formatting macros emit their ANSI escapes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ColorChoice {
+    /// Only when the stream is a terminal and `NO_COLOR` is unset or empty.
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+/// The stream whose terminal decides, in `ColorChoice::Auto`, whether the
+/// escapes are emitted: standard error for `eprintf!`, standard output for
+/// the other macros except `writef!`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Stream {
+    Stdout,
+    Stderr,
+    /// The file, buffer or string of `writef!`, which is not known to be a
+    /// terminal: the escapes are only emitted with `ColorChoice::Always`.
+    Writer,
+}
+
+static CHOICE: AtomicU8 = AtomicU8::new(0);
+
+/// Returns the current color choice.
+pub fn color_choice() -> ColorChoice {
+    match CHOICE.load(Ordering::Relaxed) {
+        1 => ColorChoice::Always,
+        2 => ColorChoice::Never,
+        _ => ColorChoice::Auto,
+    }
+}
+
+/// Overrides the detection, e.g. for a `--color=always|never|auto` flag.
+pub fn set_color_choice(choice: ColorChoice) {
+    let value = match choice {
+        ColorChoice::Auto => 0,
+        ColorChoice::Always => 1,
+        ColorChoice::Never => 2,
+    };
+    CHOICE.store(value, Ordering::Relaxed);
+}
+
+/// Whether the escapes are emitted for output going to `stream`.
+pub fn color_enabled(stream: Stream) -> bool {
+    match color_choice() {
+        ColorChoice::Always => true,
+        ColorChoice::Never => false,
+        ColorChoice::Auto => {
+            static DETECTED: OnceLock<[bool; 2]> = OnceLock::new();
+            let detected = DETECTED.get_or_init(|| {
+                let no_color = std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+                [!no_color && std::io::stdout().is_terminal(), !no_color && std::io::stderr().is_terminal()]
+            });
+            match stream {
+                Stream::Stdout => detected[0],
+                Stream::Stderr => detected[1],
+                Stream::Writer => false,
+            }
+        }
+    }
+}
+
+/// Returns `code` if escapes are enabled for `stream`, or else nothing.
+pub fn escape(code: &'static str, stream: Stream) -> &'static str {
+    if color_enabled(stream) {
+        code
+    } else {
+        ""
+    }
+}
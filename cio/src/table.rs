@@ -0,0 +1,294 @@
+// table.rs
+//! Runtime formatter behind the `:t` (boxed) and `:tm` (Markdown) table
+//! specs of the formatting macros.
+
+use crate::debug_tree::sort_by_text;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, LinkedList, VecDeque};
+use std::fmt::{Debug, Display};
+use std::hash::BuildHasher;
+use unicode_width::UnicodeWidthStr;
+
+pub use cio_macros::Row;
+
+/// A record shown as one row of a table. `#[derive(Row)]` implements it for
+/// structs with named fields, whose names become the headers.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` cannot be a row of a table",
+    label = "not a row",
+    note = "add `#[derive(cio::table::Row)]` to your struct, or use tuples, sequences or scalars"
+)]
+pub trait Row {
+    /// The column names, or `None` for rows of positional cells such as
+    /// tuples, sequences and scalars, whose columns are numbered.
+    fn headers() -> Option<Vec<String>> {
+        None
+    }
+
+    /// The text of each cell of the row.
+    fn cells(&self) -> Vec<String>;
+}
+
+/// A value shown as a table: sequences of rows, and maps whose keys make
+/// the first column.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` cannot be formatted as a table",
+    label = "not a sequence or map of rows",
+    note = "`:t` and `:tm` apply to sequences and maps of `Row` values"
+)]
+pub trait Table {
+    /// The headers and the rows of the table, with the rows of maps ordered
+    /// by key if `sorted`.
+    fn table(&self, sorted: bool) -> (Vec<String>, Vec<Vec<String>>);
+}
+
+/// Formats `value` for the `:t` spec as a table drawn with box characters,
+/// or for the `:tm` spec as a Markdown table. Columns whose cells are all
+/// numbers are right-aligned, and padding counts the terminal width of
+/// the text, so wide characters such as CJK line up. With `+sorted`, the
+/// rows of maps are ordered by key, as `:a+sorted` orders them.
+pub fn format_table<T: Table + ?Sized>(value: &T, markdown: bool, sorted: bool) -> String {
+    let (headers, rows) = value.table(sorted);
+    let columns = rows.iter().map(Vec::len).chain([headers.len()]).max().unwrap_or(0);
+    let mut widths = vec![0; columns];
+    let mut numeric = vec![true; columns];
+    for (i, header) in headers.iter().enumerate() {
+        widths[i] = header.width();
+    }
+    for row in &rows {
+        for (i, cell) in row.iter().enumerate() {
+            widths[i] = widths[i].max(cell.width());
+            numeric[i] &= cell.trim().parse::<f64>().is_ok();
+        }
+    }
+    // Markdown needs at least three dashes in its delimiter row.
+    if markdown {
+        widths.iter_mut().for_each(|width| *width = (*width).max(3));
+    }
+    let line = |cells: &[String], result: &mut String| {
+        result.push_str(if markdown { "|" } else { "│" });
+        for (i, width) in widths.iter().enumerate() {
+            let cell = cells.get(i).map_or("", String::as_str);
+            let padding = " ".repeat(width - cell.width());
+            if numeric[i] && !rows.is_empty() {
+                result.push_str(&format!(" {}{} ", padding, cell));
+            } else {
+                result.push_str(&format!(" {}{} ", cell, padding));
+            }
+            result.push_str(if markdown { "|" } else { "│" });
+        }
+        result.push('\n');
+    };
+    let rule = |left: &str, middle: &str, right: &str, result: &mut String| {
+        let bars: Vec<String> = widths.iter().map(|width| "─".repeat(width + 2)).collect();
+        result.push_str(&format!("{}{}{}\n", left, bars.join(middle), right));
+    };
+    let mut result = String::new();
+    if markdown {
+        line(&headers, &mut result);
+        result.push('|');
+        for (i, width) in widths.iter().enumerate() {
+            let dashes = "-".repeat(*width);
+            let delimiter = if numeric[i] && !rows.is_empty() { format!(" {}: |", &dashes[1..]) } else { format!(" {} |", dashes) };
+            result.push_str(&delimiter);
+        }
+        result.push('\n');
+        rows.iter().for_each(|row| line(row, &mut result));
+    } else {
+        rule("┌", "┬", "┐", &mut result);
+        line(&headers, &mut result);
+        rule("├", "┼", "┤", &mut result);
+        rows.iter().for_each(|row| line(row, &mut result));
+        rule("└", "┴", "┘", &mut result);
+    }
+    result.pop();
+    result
+}
+
+/// Headers of a table of `R` rows: their names, or else numbers.
+fn headers<R: Row>(columns: usize) -> Vec<String> {
+    R::headers().unwrap_or_else(|| (0..columns).map(|i| i.to_string()).collect())
+}
+
+fn sequence_table<'a, R: Row + 'a>(rows: impl Iterator<Item = &'a R>) -> (Vec<String>, Vec<Vec<String>>) {
+    let rows: Vec<Vec<String>> = rows.map(Row::cells).collect();
+    let columns = rows.iter().map(Vec::len).max().unwrap_or(0);
+    (headers::<R>(columns), rows)
+}
+
+fn map_table<'a, K: Display + 'a, V: Row + 'a>(
+    entries: impl Iterator<Item = (&'a K, &'a V)>,
+    sorted: bool,
+) -> (Vec<String>, Vec<Vec<String>>) {
+    let mut rows: Vec<Vec<String>> = entries
+        .map(|(key, value)| std::iter::once(key.to_string()).chain(value.cells()).collect())
+        .collect();
+    if sorted {
+        sort_by_text(&mut rows, |row| row[0].clone());
+    }
+    let columns = rows.iter().map(|row| row.len() - 1).max().unwrap_or(1);
+    let values = match V::headers() {
+        Some(headers) => headers,
+        None if columns == 1 => vec!["value".to_string()],
+        None => headers::<V>(columns),
+    };
+    (std::iter::once("key".to_string()).chain(values).collect(), rows)
+}
+
+impl<R: Row> Table for [R] {
+    fn table(&self, _sorted: bool) -> (Vec<String>, Vec<Vec<String>>) {
+        sequence_table(self.iter())
+    }
+}
+
+impl<R: Row, const N: usize> Table for [R; N] {
+    fn table(&self, _sorted: bool) -> (Vec<String>, Vec<Vec<String>>) {
+        sequence_table(self.iter())
+    }
+}
+
+impl<R: Row> Table for Vec<R> {
+    fn table(&self, _sorted: bool) -> (Vec<String>, Vec<Vec<String>>) {
+        sequence_table(self.iter())
+    }
+}
+
+impl<R: Row> Table for VecDeque<R> {
+    fn table(&self, _sorted: bool) -> (Vec<String>, Vec<Vec<String>>) {
+        sequence_table(self.iter())
+    }
+}
+
+impl<R: Row> Table for LinkedList<R> {
+    fn table(&self, _sorted: bool) -> (Vec<String>, Vec<Vec<String>>) {
+        sequence_table(self.iter())
+    }
+}
+
+impl<R: Row> Table for BTreeSet<R> {
+    fn table(&self, _sorted: bool) -> (Vec<String>, Vec<Vec<String>>) {
+        sequence_table(self.iter())
+    }
+}
+
+impl<R: Row, S: BuildHasher> Table for HashSet<R, S> {
+    fn table(&self, sorted: bool) -> (Vec<String>, Vec<Vec<String>>) {
+        let (headers, mut rows) = sequence_table(self.iter());
+        if sorted {
+            // Stable sorts from the last column to the first order the rows
+            // by their first cell, then their second, and so on.
+            let columns = rows.iter().map(Vec::len).max().unwrap_or(0);
+            for column in (0..columns).rev() {
+                sort_by_text(&mut rows, |row| row.get(column).cloned().unwrap_or_default());
+            }
+        }
+        (headers, rows)
+    }
+}
+
+impl<K: Display, V: Row, S: BuildHasher> Table for HashMap<K, V, S> {
+    fn table(&self, sorted: bool) -> (Vec<String>, Vec<Vec<String>>) {
+        map_table(self.iter(), sorted)
+    }
+}
+
+impl<K: Display, V: Row> Table for BTreeMap<K, V> {
+    fn table(&self, sorted: bool) -> (Vec<String>, Vec<Vec<String>>) {
+        map_table(self.iter(), sorted)
+    }
+}
+
+impl<T: Table + ?Sized> Table for &T {
+    fn table(&self, sorted: bool) -> (Vec<String>, Vec<Vec<String>>) {
+        (**self).table(sorted)
+    }
+}
+
+/// Scalars are rows of one cell, for tables of maps such as
+/// `HashMap<String, f64>`.
+macro_rules! scalar_rows {
+    ($($t:ty),*) => {$(
+        impl Row for $t {
+            fn cells(&self) -> Vec<String> {
+                vec![self.to_string()]
+            }
+        }
+    )*};
+}
+
+scalar_rows!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64, bool, char, String, str);
+
+impl<R: Row + ?Sized> Row for &R {
+    fn headers() -> Option<Vec<String>> {
+        R::headers()
+    }
+
+    fn cells(&self) -> Vec<String> {
+        (**self).cells()
+    }
+}
+
+impl<T: Display> Row for Vec<T> {
+    fn cells(&self) -> Vec<String> {
+        self.iter().map(ToString::to_string).collect()
+    }
+}
+
+impl<T: Display> Row for [T] {
+    fn cells(&self) -> Vec<String> {
+        self.iter().map(ToString::to_string).collect()
+    }
+}
+
+impl<T: Display, const N: usize> Row for [T; N] {
+    fn cells(&self) -> Vec<String> {
+        self.iter().map(ToString::to_string).collect()
+    }
+}
+
+macro_rules! tuple_rows {
+    ($(($($name:ident $index:tt),+))*) => {$(
+        impl<$($name: Display),+> Row for ($($name,)+) {
+            fn cells(&self) -> Vec<String> {
+                vec![$(self.$index.to_string()),+]
+            }
+        }
+    )*};
+}
+
+tuple_rows! {
+    (A 0)
+    (A 0, B 1)
+    (A 0, B 1, C 2)
+    (A 0, B 1, C 2, D 3)
+    (A 0, B 1, C 2, D 3, E 4)
+    (A 0, B 1, C 2, D 3, E 4, F 5)
+    (A 0, B 1, C 2, D 3, E 4, F 5, G 6)
+    (A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7)
+}
+
+/// Wraps a field for `#[derive(Row)]`, which formats it with `Display` if
+/// it has it, or else with `Debug`.
+#[doc(hidden)]
+pub struct Cell<'a, T: ?Sized>(pub &'a T);
+
+#[doc(hidden)]
+pub trait DisplayCell {
+    fn cell(&self) -> String;
+}
+
+impl<T: Display + ?Sized> DisplayCell for Cell<'_, T> {
+    fn cell(&self) -> String {
+        self.0.to_string()
+    }
+}
+
+#[doc(hidden)]
+pub trait DebugCell {
+    fn cell(&self) -> String;
+}
+
+impl<T: Debug + ?Sized> DebugCell for &Cell<'_, T> {
+    fn cell(&self) -> String {
+        format!("{:?}", self.0)
+    }
+}
@@ -0,0 +1,50 @@
+use cio::{sprintf, writef};
+use std::cell::Cell;
+
+#[test]
+fn positional_arguments() {
+    let name = "Ada";
+    assert_eq!(sprintf!("{name} scored {}", 42), "Ada scored 42");
+    assert_eq!(sprintf!("{} + {} = {}", 1, 2, 1 + 2), "1 + 2 = 3");
+    assert_eq!(sprintf!("{1} {0} {1}", "a", "b"), "b a b");
+    assert_eq!(sprintf!("{:>5}|{:.2f}|{!r}", 7, 1.0, "x",), "    7|1.00|\"x\"");
+}
+
+#[test]
+fn named_arguments() {
+    let (a, b) = (3, 4);
+    assert_eq!(sprintf!("{total}", total = a + b), "7");
+    assert_eq!(sprintf!("{a} and {a=}", a = "shadowed"), "shadowed and a=\"shadowed\"");
+    assert_eq!(sprintf!("{items:c} x{n:03}", items = vec![1, 2], n = 5), "[1, 2] x005");
+}
+
+#[test]
+fn nested_fields_take_arguments() {
+    let pi = std::f64::consts::PI;
+    assert_eq!(sprintf!("[{:>{}.{}}]", pi, 8, 3), "[   3.142]");
+    assert_eq!(sprintf!("[{pi:{fill}^{width}.2}]", fill = '*', width = 8), "[**3.14**]");
+}
+
+#[test]
+fn arguments_are_evaluated_once() {
+    let calls = Cell::new(0);
+    let next = || {
+        calls.set(calls.get() + 1);
+        calls.get()
+    };
+    assert_eq!(sprintf!("{0} {0} {n} {n}", next(), n = next()), "1 1 2 2");
+    assert_eq!(calls.get(), 2);
+}
+
+#[test]
+fn integer_placeholders_without_arguments() {
+    assert_eq!(sprintf!("{299792458:_}"), "299_792_458");
+}
+
+#[test]
+fn writef_arguments() {
+    use std::fmt::Write;
+    let mut out = String::new();
+    writef!(out, "{}: {total:>4}", "sum", total = 12).unwrap();
+    assert_eq!(out, "sum:   12\n");
+}
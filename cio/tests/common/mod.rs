@@ -0,0 +1,50 @@
+// Shared by the tests of what the macros print: each scenario runs one of
+// the `child_*` tests in a child process, with piped standard streams.
+// Outside of a child, those tests do nothing.
+#![allow(dead_code)]
+
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+/// Marks the start and the end of what a child test printed, apart from the
+/// lines of the test harness.
+pub const START: &str = "\u{2}";
+pub const END: &str = "\u{3}";
+
+pub fn is_child() -> bool {
+    std::env::var_os("CIO_TEST_CHILD").is_some()
+}
+
+pub fn run(test: &str, stdin: &str) -> Output {
+    run_with_env(test, stdin, &[])
+}
+
+/// Like `run`, with extra environment variables for the child.
+pub fn run_with_env(test: &str, stdin: &str, env: &[(&str, &str)]) -> Output {
+    let mut child = Command::new(std::env::current_exe().unwrap())
+        .args([test, "--exact", "--nocapture", "--test-threads=1"])
+        .env("CIO_TEST_CHILD", "1")
+        .envs(env.iter().copied())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(stdin.as_bytes()).unwrap();
+    child.wait_with_output().unwrap()
+}
+
+pub fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+pub fn stderr(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stderr).into_owned()
+}
+
+/// What the child printed between `START` and `END`.
+pub fn printed(text: &str) -> &str {
+    let start = text.find(START).expect("no start marker") + START.len();
+    let end = text[start..].find(END).expect("no end marker");
+    &text[start..start + end]
+}
@@ -0,0 +1,7 @@
+#[test]
+fn compile_fail() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/*.rs");
+    #[cfg(not(feature = "serde"))]
+    t.compile_fail("tests/ui/no_serde/*.rs");
+}
@@ -0,0 +1,299 @@
+use cio::container::{format_array, format_container, format_matrix, format_compact, format_pretty, set_summary, summary, Summary};
+use quickcheck::{quickcheck, Arbitrary, Gen};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fmt;
+
+#[test]
+fn format_array_indents_each_level() {
+    assert_eq!(format_array("[1, 2]", Summary::default(), false), "[1, 2]");
+    assert_eq!(format_array("[[1, 2], [3, 4]]", Summary::default(), false), "[\n    [1, 2],\n    [3, 4]\n]");
+    assert_eq!(
+        format_array("[[[1], [2]], [[3], [4]]]", Summary::default(), false),
+        "[\n    [\n        [1],\n        [2]\n    ],\n    [\n        [3],\n        [4]\n    ]\n]"
+    );
+}
+
+#[test]
+fn format_array_handles_any_depth() {
+    let expected = "[\n    [\n        [\n            [\n                [\n                    [1, 2]\n                ]\n            ]\n        ]\n    ]\n]";
+    assert_eq!(format_array("[[[[[[1, 2]]]]]]", Summary::default(), false), expected);
+    assert_eq!(format_array("[[1], 2, [3]]", Summary::default(), false), "[\n    [1],\n    2,\n    [3]\n]");
+}
+
+#[test]
+fn format_array_ignores_brackets_in_literals() {
+    let strings = vec![vec!["[[", "], ["], vec!["\"]\"", "a, b"]];
+    assert_eq!(
+        format_container(&strings, Summary::default(), false),
+        "[\n    [\"[[\", \"], [\"],\n    [\"\\\"]\\\"\", \"a, b\"]\n]"
+    );
+    let chars = vec![vec!['[', ']'], vec!['\'', ',']];
+    assert_eq!(format_container(&chars, Summary::default(), false), "[\n    ['[', ']'],\n    ['\\'', ',']\n]");
+}
+
+#[test]
+fn format_array_keeps_tuples_and_structs_whole() {
+    let pairs = vec![(1, vec![2, 3]), (4, vec![5])];
+    assert_eq!(format_container(&pairs, Summary::default(), false), "[(1, [2, 3]), (4, [5])]");
+    let options = vec![vec![Some(1)], vec![None]];
+    assert_eq!(format_container(&options, Summary::default(), false), "[\n    [Some(1)],\n    [None]\n]");
+}
+
+#[test]
+fn format_container_dispatches_on_debug_shape() {
+    assert_eq!(format_container(&vec![vec![1], vec![2]], Summary::default(), false), "[\n    [1],\n    [2]\n]");
+    assert_eq!(format_container(&BTreeMap::from([("a", 1)]), Summary::default(), false), "{\"a\": 1}");
+    assert_eq!(format_container(&Some(3), Summary::default(), false), "Some(3)");
+    assert_eq!(format_container("text", Summary::default(), false), "\"text\"");
+    let long: BTreeMap<i32, String> = (0..10).map(|i| (i, "x".repeat(10))).collect();
+    assert_eq!(format_container(&long, Summary::default(), false), format!("{:#?}", long));
+}
+
+#[test]
+fn format_matrix_right_aligns_each_column() {
+    let matrix = vec![vec![1, 200], vec![30, 4]];
+    assert_eq!(format_matrix(&matrix, None, false), "[[ 1, 200],\n [30,   4]]");
+    let words = vec![vec!["a", "bb"], vec!["ccc", "d"]];
+    assert_eq!(format_matrix(&words, None, false), "[[  \"a\", \"bb\"],\n [\"ccc\",  \"d\"]]");
+}
+
+#[test]
+fn format_matrix_rounds_floats_to_precision() {
+    let matrix = vec![vec![1.0, 2.5], vec![-3.14659, 10.0]];
+    assert_eq!(format_matrix(&matrix, Some(2), false), "[[ 1.00,  2.50],\n [-3.15, 10.00]]");
+    let mixed = vec![vec![1, 2], vec![3, 4]];
+    assert_eq!(format_matrix(&mixed, Some(2), false), "[[1, 2],\n [3, 4]]");
+}
+
+#[test]
+fn format_matrix_adds_index_headers() {
+    let matrix = vec![vec![1, 200, 3], vec![30, 4, 5]];
+    assert_eq!(format_matrix(&matrix, None, true), "    0    1  2\n0   1  200  3\n1  30    4  5");
+    let wide: Vec<Vec<u8>> = (0..11).map(|i| vec![i]).collect();
+    assert!(format_matrix(&wide, None, true).ends_with("\n10  10"));
+}
+
+#[test]
+fn format_matrix_falls_back_to_containers() {
+    let ragged = vec![vec![1, 2], vec![3]];
+    assert_eq!(format_matrix(&ragged, None, false), format_container(&ragged, Summary::default(), false));
+    let flat = vec![1, 2, 3];
+    assert_eq!(format_matrix(&flat, None, true), "[1, 2, 3]");
+    let empty: Vec<Vec<i32>> = Vec::new();
+    assert_eq!(format_matrix(&empty, None, false), "[]");
+    assert_eq!(format_matrix(&empty, None, true), "[]");
+}
+
+#[test]
+fn format_matrix_of_empty_rows_is_a_container() {
+    let rows: Vec<Vec<i32>> = vec![Vec::new(), vec![]];
+    let expected = format_container(&rows, Summary::default(), false);
+    assert_eq!(format_matrix(&rows, None, false), expected);
+    assert_eq!(format_matrix(&rows, None, true), expected);
+    assert_eq!(cio::sprintf!("{rows:mi}"), expected);
+}
+
+#[test]
+fn long_sequences_are_summarized() {
+    let summary = Summary { threshold: 5, edge: 2 };
+    let numbers: Vec<i32> = (1..=1000).collect();
+    assert_eq!(format_container(&numbers, summary, false), "[1, 2, ..., 999, 1000] (1000 items)");
+    let short = vec![1, 2, 3, 4, 5];
+    assert_eq!(format_container(&short, summary, false), "[1, 2, 3, 4, 5]");
+    let default = format_container(&(1..=1001).collect::<Vec<_>>(), Summary::default(), false);
+    assert_eq!(default, "[1, 2, 3, ..., 999, 1000, 1001] (1001 items)");
+}
+
+#[test]
+fn summarization_applies_per_nesting_level() {
+    let summary = Summary { threshold: 3, edge: 1 };
+    let matrix: Vec<Vec<i32>> = (0..5).map(|i| (0..i).collect()).collect();
+    assert_eq!(
+        format_container(&matrix, summary, false),
+        "[\n    [],\n    ...,\n    [0, ..., 3] (4 items)\n] (5 items)"
+    );
+}
+
+#[test]
+fn long_maps_are_summarized() {
+    let summary = Summary { threshold: 2, edge: 1 };
+    let map: BTreeMap<i32, &str> = BTreeMap::from([(1, "a"), (2, "b"), (3, "c")]);
+    assert_eq!(format_pretty(&map, summary, false), "{\n    1: \"a\",\n    ...,\n    3: \"c\",\n} (3 items)");
+    assert_eq!(format_container(&map, summary, false), "{1: \"a\", ..., 3: \"c\"} (3 items)");
+    assert_eq!(format_pretty(&map, Summary::default(), false), format!("{:#?}", map));
+}
+
+#[test]
+fn sorted_orders_maps_and_sets_at_any_depth() {
+    let inner: HashSet<i32> = HashSet::from([10, -2, 3]);
+    let map: HashMap<&str, HashSet<i32>> = HashMap::from([("b", inner.clone()), ("a", inner), ("c", HashSet::new())]);
+    let expected = "{\"a\": {-2, 3, 10}, \"b\": {-2, 3, 10}, \"c\": {}}";
+    assert_eq!(format_compact(&map, true), expected);
+    assert_eq!(format_container(&map, Summary::default(), true), expected);
+    let nested = vec![HashMap::from([(2, 'b'), (1, 'a')])];
+    assert_eq!(format_compact(&nested, true), "[{1: 'a', 2: 'b'}]");
+    assert_eq!(format_pretty(&HashSet::from(["y", "x"]), Summary::default(), true), "{\n    \"x\",\n    \"y\",\n}");
+}
+
+#[test]
+fn sorted_puts_numbers_before_other_keys() {
+    // Prints bare, like numbers and enum variants.
+    #[derive(PartialEq, Eq, Hash)]
+    struct Key(&'static str);
+    impl fmt::Debug for Key {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str(self.0)
+        }
+    }
+    let set: HashSet<Key> = ["10", "9a", "9", "Red", "2.5", "-1", "Blue"].into_iter().map(Key).collect();
+    assert_eq!(format_compact(&set, true), "{-1, 2.5, 9, 10, 9a, Blue, Red}");
+    let map: HashMap<Key, i32> = HashMap::from([(Key("b"), 1), (Key("10"), 2), (Key("9"), 3), (Key("9a"), 4)]);
+    assert_eq!(format_compact(&map, true), "{9: 3, 10: 2, 9a: 4, b: 1}");
+}
+
+#[test]
+fn sorted_keeps_struct_fields_in_declaration_order() {
+    #[derive(Debug)]
+    #[allow(dead_code)]
+    struct Point {
+        y: i32,
+        x: i32,
+    }
+    assert_eq!(format_compact(&Point { y: 1, x: 2 }, true), "Point { y: 1, x: 2 }");
+}
+
+#[test]
+fn global_summary_can_be_changed_at_runtime() {
+    let original = summary();
+    set_summary(Summary { threshold: 10, edge: 1 });
+    assert_eq!(summary(), Summary { threshold: 10, edge: 1 });
+    set_summary(original);
+    assert_eq!(summary(), Summary::default());
+}
+
+/// Arbitrarily nested sequences of strings and chars, with some tuples,
+/// structs and maps.
+#[derive(Clone)]
+enum Tree {
+    Text(String),
+    Char(char),
+    Seq(Vec<Tree>),
+    Pair(Box<Tree>, Box<Tree>),
+    Record(Option<Box<Tree>>),
+    Map(BTreeMap<String, Tree>),
+}
+
+impl fmt::Debug for Tree {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Tree::Text(text) => fmt::Debug::fmt(text, f),
+            Tree::Char(c) => fmt::Debug::fmt(c, f),
+            Tree::Seq(items) => fmt::Debug::fmt(items, f),
+            Tree::Pair(first, second) => fmt::Debug::fmt(&(first, second), f),
+            Tree::Record(field) => f.debug_struct("Record").field("field", field).finish(),
+            Tree::Map(map) => fmt::Debug::fmt(map, f),
+        }
+    }
+}
+
+impl Tree {
+    fn arbitrary_with_depth(g: &mut Gen, depth: usize, structured: bool) -> Tree {
+        let kinds = if structured { 7 } else { 4 };
+        let child = |g: &mut Gen| Box::new(Tree::arbitrary_with_depth(g, depth + 1, structured));
+        match u8::arbitrary(g) % kinds {
+            0 if depth > 0 => Tree::Text(String::arbitrary(g)),
+            1 if depth > 0 => Tree::Char(char::arbitrary(g)),
+            4 if depth < 6 => Tree::Pair(child(g), child(g)),
+            5 if depth < 6 => Tree::Record(bool::arbitrary(g).then(|| child(g))),
+            6 if depth < 6 => {
+                let len = usize::arbitrary(g) % 4;
+                Tree::Map((0..len).map(|_| (String::arbitrary(g), *child(g))).collect())
+            }
+            _ if depth < 6 => {
+                let len = usize::arbitrary(g) % 4;
+                Tree::Seq((0..len).map(|_| *child(g)).collect())
+            }
+            _ => Tree::Text(String::arbitrary(g)),
+        }
+    }
+}
+
+/// A `Tree` that also holds tuples, structs and maps.
+#[derive(Clone, Debug)]
+struct StructuredTree(Tree);
+
+impl Arbitrary for StructuredTree {
+    fn arbitrary(g: &mut Gen) -> StructuredTree {
+        StructuredTree(Tree::arbitrary_with_depth(g, 0, true))
+    }
+}
+
+impl Arbitrary for Tree {
+    fn arbitrary(g: &mut Gen) -> Tree {
+        Tree::arbitrary_with_depth(g, 0, false)
+    }
+}
+
+/// Undoes the line breaks and indentation added by `format_array`. `Debug`
+/// escapes newlines in strings and chars, so every raw newline was added.
+fn collapse(pretty: &str) -> String {
+    let mut compact = String::with_capacity(pretty.len());
+    let mut chars = pretty.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\n' {
+            compact.push(c);
+            continue;
+        }
+        while chars.peek() == Some(&' ') {
+            chars.next();
+        }
+        if compact.ends_with(',') {
+            compact.push(' ');
+        }
+    }
+    compact
+}
+
+quickcheck! {
+    fn rendering_without_elision_matches_pretty_debug(tree: StructuredTree) -> bool {
+        let everything = Summary { threshold: 0, edge: usize::MAX / 2 };
+        format_pretty(&tree.0, everything, false) == format!("{:#?}", tree.0)
+    }
+
+    fn summarized_form_keeps_edges(len: u8) -> bool {
+        let numbers: Vec<u8> = (0..len).collect();
+        let summarized = format_container(&numbers, Summary { threshold: 10, edge: 2 }, false);
+        match len {
+            0..=10 => summarized == format!("{:?}", numbers),
+            _ => summarized == format!("[0, 1, ..., {}, {}] ({} items)", len - 2, len - 1, len),
+        }
+    }
+
+    fn sorted_hash_collections_match_btree_ones(map: HashMap<i16, String>, set: HashSet<i64>) -> bool {
+        let sorted_map: BTreeMap<_, _> = map.iter().collect();
+        let sorted_set: BTreeSet<_> = set.iter().collect();
+        format_compact(&map, true) == format!("{:?}", sorted_map)
+            && format_pretty(&set, Summary::default(), true) == format!("{:#?}", sorted_set)
+    }
+
+    fn pretty_form_collapses_to_compact_form(tree: Tree) -> bool {
+        let compact = format!("{:?}", tree);
+        collapse(&format_array(&compact, Summary::default(), false)) == compact
+    }
+
+    fn pretty_form_indents_by_nesting_level(tree: Tree) -> bool {
+        let pretty = format_array(&format!("{:?}", tree), Summary::default(), false);
+        let mut level = 0usize;
+        pretty.lines().all(|line| {
+            let content = line.trim_start_matches(' ');
+            if content.starts_with(']') {
+                level = level.saturating_sub(1);
+            }
+            let indented = line.len() - content.len() == 4 * level;
+            if content.ends_with('[') {
+                level += 1;
+            }
+            indented
+        })
+    }
+}
@@ -0,0 +1,176 @@
+mod common;
+
+use cio::prompt::TokenError;
+use cio::{input, try_input, InputError};
+use common::{is_child, run, stdout};
+use std::num::ParseIntError;
+
+// `input!` reads the standard input of the process, so each scenario runs
+// one of the `child_*` tests through `common::run`, fed with `stdin`.
+
+#[test]
+fn child_input_age() {
+    if is_child() {
+        let age: u32 = input!("Age: ");
+        println!("<{age}>");
+    }
+}
+
+#[test]
+fn child_try_input_age() {
+    if is_child() {
+        let age: Result<u32, InputError<ParseIntError>> = try_input!("Age: ");
+        report(age);
+    }
+}
+
+#[test]
+fn child_try_input_fail_fast() {
+    if is_child() {
+        report(try_input!("Age: ", retries = 0));
+        let retries = 1;
+        report(try_input!("Age: ", retries = retries,));
+    }
+}
+
+fn report(age: Result<u32, InputError<ParseIntError>>) {
+    match age {
+        Ok(age) => println!("<{age}>"),
+        Err(InputError::Eof) => println!("<eof>"),
+        Err(InputError::Empty) => println!("<empty>"),
+        Err(InputError::Parse(e)) => println!("<parse: {e:?}>"),
+        Err(e) => println!("<{e}>"),
+    }
+}
+
+#[test]
+fn input_retries_until_valid() {
+    let output = run("child_input_age", "\nabc\n42\n");
+    let stdout = stdout(&output);
+    assert!(stdout.contains("Error: Unauthorized empty input."));
+    assert!(stdout.contains("Error: invalid digit found in string."));
+    assert!(stdout.contains("<42>"));
+    assert!(output.status.success());
+}
+
+#[test]
+fn input_exits_on_eof() {
+    let output = run("child_input_age", "abc\n");
+    assert!(!output.status.success());
+    assert!(!stdout(&output).contains('<'));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Error: end of input: standard input was closed while waiting for \"Age:\"."));
+}
+
+#[test]
+fn input_reads_last_line_without_newline() {
+    assert!(stdout(&run("child_input_age", "7")).contains("<7>"));
+}
+
+#[test]
+fn try_input_reports_eof() {
+    assert!(stdout(&run("child_try_input_age", "")).contains("<eof>"));
+    assert!(stdout(&run("child_try_input_age", "x\n")).contains("<eof>"));
+}
+
+#[test]
+fn try_input_gives_up_after_retries() {
+    let output = run("child_try_input_age", "a\nb\n\nd\n5\n");
+    let stdout = stdout(&output);
+    assert!(stdout.contains("<parse: ParseIntError { kind: InvalidDigit }>"));
+    assert_eq!(stdout.matches("Age: ").count(), 4);
+}
+
+#[test]
+fn try_input_retry_limit() {
+    let stdout = stdout(&run("child_try_input_fail_fast", "\n-1\n12\n"));
+    assert!(stdout.contains("<empty>"));
+    assert!(stdout.contains("Error: invalid digit found in string."));
+    assert!(stdout.contains("<12>"));
+    let stdout = self::stdout(&run("child_try_input_fail_fast", "x\ny\nz\n"));
+    assert_eq!(stdout.matches("<parse: ParseIntError { kind: InvalidDigit }>").count(), 2);
+    assert_eq!(stdout.matches("Age: ").count(), 3);
+}
+
+#[test]
+fn child_defaults() {
+    if is_child() {
+        let port: u16 = input!("Port", default = 8080);
+        let host: String = input!("Host: ", default = "localhost".to_string());
+        let nickname: Option<String> = input!("Nickname: ", allow_empty);
+        let age: Result<Option<u8>, _> = try_input!("Age: ", allow_empty = true, retries = 0);
+        println!("<{port} {host} {nickname:?} {age:?}>");
+    }
+}
+
+#[test]
+fn child_checks() {
+    if is_child() {
+        let age: u8 = input!("Age: ", validate = |a: &u8| *a >= 18, message = "must be adult");
+        let score: i32 = input!("Score: ", range = 1..=100);
+        let code: String = input!("Code: ", matches = r"^[A-Z]{3}$");
+        let size: String = input!("Size: ", one_of = ["S", "M", "L"]);
+        let level: Result<u8, _> = try_input!("Level: ", range = 1..4, message = format!("pick 1 to {}", 3), retries = 0);
+        println!("<{age} {score} {code} {size} {level:?}>");
+    }
+}
+
+#[test]
+fn checks_prompt_again_with_their_message() {
+    let output = run("child_checks", "17\n18\n0\n101\n50\nabcd\nABC\nXL\nM\n9\n");
+    let stdout = stdout(&output);
+    assert!(stdout.contains("Age: Error: must be adult.\nAge: Score: "));
+    assert!(stdout.contains("Error: must be in 1..=100.\nScore: Error: must be in 1..=100.\nScore: Code: "));
+    assert!(stdout.contains("Error: must match `^[A-Z]{3}$`.\nCode: Size: "));
+    assert!(stdout.contains("Error: must be one of [\"S\", \"M\", \"L\"].\nSize: Level: "));
+    assert!(stdout.contains("Error: pick 1 to 3.\n"));
+    assert!(stdout.contains("<18 50 ABC M Err(Invalid(\"pick 1 to 3\"))>"));
+    assert!(output.status.success());
+}
+
+#[test]
+fn checks_apply_after_parsing() {
+    let stdout = stdout(&run("child_checks", "\nadult\n20\n"));
+    assert!(stdout.contains("Error: Unauthorized empty input.\nAge: Error: invalid digit found in string.\nAge: Score: "));
+}
+
+#[test]
+fn child_values() {
+    if is_child() {
+        let (a, b, c): (i32, f64, String) = input!("Point: ");
+        let xs: Vec<i64> = input!("Numbers: ", sep = ",");
+        let pair: Result<(u8, char), InputError<TokenError>> = try_input!("Pair: ", retries = 1);
+        println!("<{a} {b} {c} {xs:?} {pair:?}>");
+    }
+}
+
+#[test]
+fn tuples_and_vectors_parse_each_value() {
+    let output = run("child_values", "1 x\n1 2.5 z\n4, 5,6\n7 8 9\n300 y\n");
+    let stdout = stdout(&output);
+    assert!(stdout.contains("Point: Error: expected 3 values, found 2.\nPoint: Numbers: Pair: "));
+    assert!(stdout.contains("Error: expected 2 values, found 3.\nPair: Error: value 1 (`300`): number too large to fit in target type.\n"));
+    assert!(stdout.contains(r#"<1 2.5 z [4, 5, 6] Err(Parse(Parse { index: 0, token: "300", message: "number too large to fit in target type" }))>"#));
+    assert!(output.status.success());
+}
+
+#[test]
+fn failed_values_are_reported() {
+    let stdout = stdout(&run("child_values", "1 x z\n1 2 z\n1,y,3\n1,2\n"));
+    assert!(stdout.contains("Error: value 2 (`x`): invalid float literal.\nPoint: Numbers: "));
+    assert!(stdout.contains("Error: value 2 (`y`): invalid digit found in string.\nNumbers: Pair: "));
+}
+
+#[test]
+fn empty_lines_take_defaults() {
+    let stdout = stdout(&run("child_defaults", "\n\n\n\n"));
+    assert!(stdout.contains("Port [8080]: Host [localhost]: Nickname: Age: "));
+    assert!(stdout.contains("<8080 localhost None Ok(None)>"));
+}
+
+#[test]
+fn defaults_are_overridden_by_values() {
+    let stdout = stdout(&run("child_defaults", "x\n80\nexample.org\nbob\n300\n"));
+    assert!(stdout.contains("Error: invalid digit found in string.\nPort [8080]: "));
+    assert!(stdout.contains("<80 example.org Some(\"bob\") Err(Parse(ParseIntError { kind: PosOverflow }))>"));
+}
\ No newline at end of file
@@ -0,0 +1,50 @@
+#![cfg(feature = "serde")]
+
+use cio::json::format_json;
+use cio::sprintf;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+
+#[derive(Serialize)]
+struct Person {
+    name: String,
+    age: u8,
+    tags: Vec<&'static str>,
+}
+
+#[test]
+fn json_is_compact_or_indented() {
+    let person = Person { name: "Alice \"Al\"".to_string(), age: 30, tags: vec!["admin"] };
+    assert_eq!(sprintf!("{person:json}"), r#"{"name":"Alice \"Al\"","age":30,"tags":["admin"]}"#);
+    assert_eq!(
+        sprintf!("{person:json#}"),
+        "{\n  \"name\": \"Alice \\\"Al\\\"\",\n  \"age\": 30,\n  \"tags\": [\n    \"admin\"\n  ]\n}"
+    );
+}
+
+#[test]
+fn json_of_std_collections() {
+    let map = BTreeMap::from([("b", vec![1.5]), ("a", vec![])]);
+    assert_eq!(sprintf!("{map:json}"), r#"{"a":[],"b":[1.5]}"#);
+    let (unit, none): ((), Option<i32>) = ((), None);
+    assert_eq!(sprintf!("{unit:json} {none:json} {'c':json}"), r#"null null "c""#);
+}
+
+#[test]
+fn sorted_json_orders_object_keys() {
+    let map = HashMap::from([("b", HashMap::from([("y", 1), ("x", 2)])), ("a", HashMap::new())]);
+    assert_eq!(sprintf!("{map:json+sorted}"), r#"{"a":{},"b":{"x":2,"y":1}}"#);
+    assert_eq!(sprintf!("{map:json#+sorted}"), "{\n  \"a\": {},\n  \"b\": {\n    \"x\": 2,\n    \"y\": 1\n  }\n}");
+}
+
+#[test]
+fn j_keeps_the_debug_pretty_print() {
+    let map = BTreeMap::from([("a", 1)]);
+    assert_eq!(sprintf!("{map:j}"), "{\n    \"a\": 1,\n}");
+}
+
+#[test]
+fn unrepresentable_values_describe_the_error() {
+    let map = HashMap::from([((1, 2), "tuple key")]);
+    assert!(format_json(&map, false, false).starts_with("<not JSON: key must be a string"));
+}
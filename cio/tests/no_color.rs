@@ -0,0 +1,43 @@
+mod common;
+
+use cio::{printf, sprintf};
+use cio::style::{self, ColorChoice, Stream};
+use common::{is_child, printed, run, run_with_env, stdout, END, START};
+
+#[test]
+fn color_choice_never_removes_escapes() {
+    style::set_color_choice(ColorChoice::Never);
+    let count = 3;
+    assert_eq!(sprintf!("[bold red]Error:[/] {count:green}"), "Error: 3");
+    assert!(!style::color_enabled(Stream::Stdout));
+    assert_eq!(style::escape("\x1b[1m", Stream::Stdout), "");
+    style::set_color_choice(ColorChoice::Auto);
+    assert_eq!(style::color_choice(), ColorChoice::Auto);
+}
+
+#[test]
+fn writef_is_plain_unless_always() {
+    use std::fmt::Write;
+    // `Auto` never styles a writer, even when stdout is a terminal.
+    assert!(!style::color_enabled(Stream::Writer));
+    let mut log = String::new();
+    cio::writef!(log, "[red]x[/]").unwrap();
+    assert_eq!(log, "x\n");
+}
+
+#[test]
+fn child_plain_output() {
+    if is_child() {
+        print!("{START}");
+        printf!("[red]x[/]");
+        print!("{END}");
+    }
+}
+
+#[test]
+fn printf_is_plain_when_piped_or_no_color() {
+    let output = run("child_plain_output", "");
+    assert_eq!(printed(&stdout(&output)), "x\n");
+    let output = run_with_env("child_plain_output", "", &[("NO_COLOR", "1")]);
+    assert_eq!(printed(&stdout(&output)), "x\n");
+}
@@ -0,0 +1,51 @@
+use cio::number::{format_number, Align, Kind, NumberSpec, Sign};
+use cio::sprintf;
+
+#[test]
+fn grouping_separators() {
+    let n = 1234567;
+    let x = 1234567.891;
+    assert_eq!(sprintf!("{n:,} {n:_} {x:,.2f} {-n:,}"), "1,234,567 1_234_567 1,234,567.89 -1,234,567");
+    assert_eq!(sprintf!("{0xDEADBEEFu32:#_x} {255:_b}"), "0xdead_beef 1111_1111");
+    assert_eq!(sprintf!("{1234:010,} {1234:08,}"), "00,001,234 0,001,234");
+}
+
+#[test]
+fn percentages() {
+    let ratio = 0.256;
+    assert_eq!(sprintf!("{ratio:.1%} {ratio:%} {1:.0%}"), "25.6% 25.600000% 100%");
+}
+
+#[test]
+fn signs_and_sign_aware_padding() {
+    let x = 42;
+    let y = -3.5;
+    assert_eq!(sprintf!("[{x: }] [{y: }] [{x:=+10}] [{y:=10}]"), "[ 42] [-3.5] [+       42] [-      3.5]");
+    assert_eq!(sprintf!("[{x:*^+9,}] [{-0.0001:z.2f}]"), "[***+42***] [0.00]");
+}
+
+#[test]
+fn general_and_fixed_formats() {
+    let general: Vec<String> = [0.0, 1e-5, 0.0001, 123.456, 1e6].iter().map(|v| sprintf!("{v:g}")).collect();
+    assert_eq!(general, ["0", "1e-05", "0.0001", "123.456", "1e+06"]);
+    assert_eq!(sprintf!("{1.0:#g} {123.456:.3g} {0.5:G} {3.0:f} {12345.0:,.5}"), "1.00000 123 0.5 3.000000 1.2345e+04");
+    let (inf, nan) = (f64::INFINITY, f64::NAN);
+    assert_eq!(sprintf!("{inf:F} {-inf:010,} {nan: }"), "INF -000000inf  nan");
+}
+
+#[test]
+fn std_specs_keep_their_rust_meaning() {
+    let pi = std::f64::consts::PI;
+    let name = "ab";
+    assert_eq!(sprintf!("{pi:.2} {pi:e} {255:x} {name:>6s}"), "3.14 3.141592653589793e0 ff     ab");
+}
+
+#[test]
+fn format_number_at_runtime() {
+    let spec = NumberSpec { grouping: Some(','), kind: Some(Kind::Fixed), precision: Some(1), ..NumberSpec::default() };
+    assert_eq!(format_number(&-1234.56f32, spec), "-1,234.6");
+    let spec = NumberSpec { fill: '0', align: Align::AfterSign, sign: Sign::Plus, width: 6, ..NumberSpec::default() };
+    assert_eq!(format_number(&7u8, spec), "+00007");
+    let spec = NumberSpec { grouping: Some('_'), ..NumberSpec::default() };
+    assert_eq!(format_number(&i128::MIN, spec), "-170_141_183_460_469_231_731_687_303_715_884_105_728");
+}
@@ -0,0 +1,27 @@
+use cio::print_py;
+
+mod common;
+use common::{is_child, printed, run, stdout, END, START};
+
+#[test]
+fn child_print_py() {
+    if is_child() {
+        let done = 3;
+        let total = 10;
+        print!("{START}");
+        print_py!("progress:", "{done}/{total}", sep = " ", end = "", flush = true);
+        print_py!(" ({done * 100 / total}%)", end = "\r");
+        print_py!("a", 1, 'c', sep = ", ");
+        print_py!("x", "y", sep = "", end = "|");
+        print_py!();
+        print_py!("last");
+        print!("{END}");
+    }
+}
+
+#[test]
+fn print_py_applies_sep_and_end() {
+    let output = run("child_print_py", "");
+    assert_eq!(printed(&stdout(&output)), "progress: 3/10 (30%)\ra, 1, c\nxy|\nlast\n");
+    assert!(output.status.success());
+}
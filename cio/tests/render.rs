@@ -0,0 +1,71 @@
+use cio::table::{format_table, Row};
+use cio::{sprintf, CioDisplay};
+use std::collections::BTreeMap;
+
+#[derive(CioDisplay)]
+struct Employee {
+    #[cio(rename = "employee")]
+    name: String,
+    #[cio(skip)]
+    #[allow(dead_code)]
+    password: String,
+    #[cio(spec = ".2")]
+    salary: f64,
+    #[cio(spec = ",", rename = "id")]
+    badge: u32,
+    tags: Vec<&'static str>,
+}
+
+fn ada() -> Employee {
+    Employee { name: "Ada".to_string(), password: "hunter2".to_string(), salary: 1234.5, badge: 1000000, tags: vec!["ops"] }
+}
+
+#[derive(CioDisplay)]
+struct Wrapper<T> {
+    value: T,
+    #[cio(rename = "n")]
+    count: usize,
+}
+
+#[test]
+fn generic_structs_bound_their_parameters() {
+    let wrapper = Wrapper { value: vec!['a'], count: 2 };
+    assert_eq!(format!("{:?}", wrapper), "Wrapper { value: ['a'], n: 2 }");
+    assert_eq!(sprintf!("{:t}", [wrapper]), "┌───────┬───┐\n│ value │ n │\n├───────┼───┤\n│ ['a'] │ 2 │\n└───────┴───┘");
+}
+
+#[test]
+fn debug_follows_attributes() {
+    let ada = ada();
+    assert_eq!(format!("{:?}", ada), r#"Employee { employee: "Ada", salary: 1234.50, id: 1,000,000, tags: ["ops"] }"#);
+    assert_eq!(sprintf!("{ada:c}"), format!("{:?}", ada));
+}
+
+#[test]
+fn container_specs_use_derived_form() {
+    let staff = BTreeMap::from([("lead", ada())]);
+    let expected = "\
+{
+    \"lead\": Employee {
+        employee: \"Ada\",
+        salary: 1234.50,
+        id: 1,000,000,
+        tags: [
+            \"ops\",
+        ],
+    },
+}";
+    assert_eq!(sprintf!("{staff:j}"), expected);
+    assert_eq!(format!("{:#?}", staff), expected);
+    assert!(!sprintf!("{staff:a}").contains("hunter2"));
+}
+
+#[test]
+fn table_rows_use_derived_form() {
+    let staff = vec![ada()];
+    assert_eq!(Employee::headers(), Some(vec!["employee".into(), "salary".into(), "id".into(), "tags".into()]));
+    assert_eq!(
+        format_table(&staff, true, false),
+        "| employee |  salary | id        | tags    |\n| -------- | ------: | --------- | ------- |\n| Ada      | 1234.50 | 1,000,000 | [\"ops\"] |"
+    );
+}
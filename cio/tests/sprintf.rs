@@ -0,0 +1,115 @@
+use cio::{f, sprintf};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+#[test]
+fn sprintf_inlines_expressions() {
+    let name = "Alice";
+    let age = 30;
+    assert_eq!(sprintf!("{name} is {age + 1} next year"), "Alice is 31 next year");
+}
+
+#[test]
+fn sprintf_supports_std_specs() {
+    let pi = std::f64::consts::PI;
+    assert_eq!(sprintf!("{pi:.2}|{42:>5}|{255:x}"), "3.14|   42|ff");
+}
+
+#[test]
+fn sprintf_keeps_std_fills_of_container_letters() {
+    let x = 5;
+    assert_eq!(sprintf!("{x:a<5}|{x:j^5}|{x:a>3}"), "5aaaa|jj5jj|aa5");
+    let v = vec![1, 2];
+    assert_eq!(sprintf!("{v:a}|{v:a<edge=1>}"), "[1, 2]|[1, 2]");
+}
+
+#[test]
+fn sprintf_supports_container_specs() {
+    let matrix = vec![vec![1, 2], vec![3, 4]];
+    let map = BTreeMap::from([("a", 1), ("b", 2)]);
+    assert_eq!(sprintf!("{matrix:c}"), "[[1, 2], [3, 4]]");
+    assert_eq!(sprintf!("{matrix:a}"), "[\n    [1, 2],\n    [3, 4]\n]");
+    assert_eq!(sprintf!("{map:j}"), "{\n    \"a\": 1,\n    \"b\": 2,\n}");
+}
+
+#[test]
+fn sprintf_scans_expressions_with_braces_and_literals() {
+    struct Point {
+        x: i32,
+        y: i32,
+    }
+    let v = [1, 2, 3];
+    assert_eq!(sprintf!("{v.iter().map(|x| { x + 1 }).sum::<i32>()}"), "9");
+    assert_eq!(sprintf!("{Point { x: 1, y: 2 }.x + Point { x: 3, y: 4 }.y:>3}"), "  5");
+    assert_eq!(sprintf!("{\"}{:\".len()} {'}'} {r#\"a\"}\"#}"), "3 } a\"}");
+    assert_eq!(sprintf!("{{literal}} {{{v.len()}}} }}{{"), "{literal} {3} }{");
+    assert_eq!(sprintf!("{None::<i32>:?} {():?} {std::f64::consts::E:.1}"), "None () 2.7");
+    assert_eq!(sprintf!("{if v.contains(&4) { \"four\" } else { \"none\" }:>6}"), "  none");
+}
+
+#[test]
+fn sprintf_supports_summary_overrides() {
+    let numbers: Vec<u32> = (1..=20).collect();
+    assert_eq!(sprintf!("{numbers:a<edge=2, threshold=10>}"), "[1, 2, ..., 19, 20] (20 items)");
+    assert_eq!(sprintf!("{numbers:j<threshold=19,edge=1>}"), "[\n    1,\n    ...,\n    20,\n] (20 items)");
+    assert_eq!(sprintf!("{numbers:a<edge=1>}"), sprintf!("{numbers:c}"));
+}
+
+#[test]
+fn sprintf_supports_sorted_modifier() {
+    let map = HashMap::from([("b", HashSet::from([2, 1])), ("a", HashSet::from([3]))]);
+    assert_eq!(sprintf!("{map:c+sorted}"), "{\"a\": {3}, \"b\": {1, 2}}");
+    assert_eq!(sprintf!("{map:a+sorted}"), sprintf!("{map:c+sorted}"));
+    assert_eq!(sprintf!("{map:j+sorted}"), "{\n    \"a\": {\n        3,\n    },\n    \"b\": {\n        1,\n        2,\n    },\n}");
+    let numbers: HashSet<u32> = (1..=20).collect();
+    assert_eq!(sprintf!("{numbers:a<edge=2, threshold=10>+sorted}"), "{1, 2, ..., 19, 20} (20 items)");
+}
+
+#[test]
+fn sprintf_supports_matrix_specs() {
+    let matrix = vec![vec![1.5, 200.0], vec![30.25, 4.0]];
+    assert_eq!(sprintf!("{matrix:m.1}"), "[[ 1.5, 200.0],\n [30.2,   4.0]]");
+    assert_eq!(sprintf!("{matrix:mi.0}"), "    0    1\n0   2  200\n1  30    4");
+    let x = 5;
+    assert_eq!(sprintf!("{x:m>5}|{x:m<3}|{x:i^3}"), "mmmm5|5mm|i5i");
+}
+
+#[test]
+fn sprintf_supports_nested_specs() {
+    let (width, precision) = (8, 2);
+    let (fill, align) = ('*', '^');
+    let pi = std::f64::consts::PI;
+    assert_eq!(sprintf!("[{pi:{width}.{precision}}] [{pi:<{width}.3}]"), "[    3.14] [3.142   ]");
+    assert_eq!(sprintf!("[{\"ab\":{fill}{align}{width}}] [{-42:{'='}{width + 1}}]"), "[***ab***] [-      42]");
+    assert_eq!(sprintf!("[{1234567:{fill}>{width + 4},}] [{0.256:{align}{width}.{precision - 1}%}]"), "[***1,234,567] [ 25.6%  ]");
+}
+
+#[test]
+fn sprintf_supports_self_documenting_placeholders() {
+    struct User {
+        name: &'static str,
+        age: u32,
+    }
+    let user = User { name: "Bob", age: 30 };
+    let matrix = vec![vec![1, 2], vec![3, 4]];
+    assert_eq!(sprintf!("{user.age=}"), "user.age=30");
+    assert_eq!(sprintf!("{user.name=} {user.age * 2 = }"), "user.name=\"Bob\" user.age * 2 = 60");
+    assert_eq!(sprintf!("{user.name = :>5}|{user.age==30}"), "user.name =   Bob|true");
+    assert_eq!(sprintf!("{matrix=:c} {matrix = :a}"), "matrix=[[1, 2], [3, 4]] matrix = [\n    [1, 2],\n    [3, 4]\n]");
+}
+
+#[test]
+fn sprintf_supports_conversion_flags() {
+    let name = "Zoë";
+    let point = (1, -2);
+    assert_eq!(sprintf!("{name!r} {name!s} {name!a}"), "\"Zoë\" Zoë \"Zo\\u{eb}\"");
+    assert_eq!(sprintf!("[{name!r:>7}] [{point.0!s:^5}]"), "[  \"Zoë\"] [  1  ]");
+    assert_eq!(sprintf!("{name=!a} {point = !r:<10}|"), "name=\"Zo\\u{eb}\" point = (1, -2)   |");
+    assert_eq!(sprintf!("{name != \"x\"}"), "true");
+}
+
+#[test]
+fn f_is_an_alias_of_sprintf() {
+    let x = 2;
+    let message: String = f!("x squared is {x * x}");
+    assert_eq!(message, "x squared is 4");
+}
@@ -0,0 +1,47 @@
+use cio::sprintf;
+use cio::style::{self, ColorChoice, Stream};
+
+// The color choice is global, so every test of this file forces colors on;
+// `no_color.rs` checks them off in its own process.
+
+#[test]
+fn markup_tags() {
+    style::set_color_choice(ColorChoice::Always);
+    let msg = "disk full";
+    assert_eq!(sprintf!("[bold red]Error:[/] {msg}"), "\x1b[1;31mError:\x1b[0m disk full");
+    assert_eq!(sprintf!("[white on blue]a[/] [on_bright_black #ff8000]b"), "\x1b[37;44ma\x1b[0m \x1b[100;38;2;255;128;0mb\x1b[0m");
+}
+
+#[test]
+fn nested_tags_restore_outer_styles() {
+    style::set_color_choice(ColorChoice::Always);
+    assert_eq!(sprintf!("[bold]a [red]b[/red] c[/bold]"), "\x1b[1ma \x1b[31mb\x1b[0m\x1b[1m c\x1b[0m");
+}
+
+#[test]
+fn style_specs() {
+    style::set_color_choice(ColorChoice::Always);
+    let count = 3;
+    assert_eq!(sprintf!("{count:green} files"), "\x1b[32m3\x1b[0m files");
+    assert_eq!(sprintf!("[bold]{count:>4 underline}[/]"), "\x1b[1m\x1b[4m   3\x1b[0m\x1b[1m\x1b[0m");
+    assert_eq!(sprintf!("{count=:dim}"), "count=\x1b[2m3\x1b[0m");
+}
+
+#[test]
+fn brackets_that_are_not_tags() {
+    style::set_color_choice(ColorChoice::Always);
+    let v = vec![1, 2];
+    assert_eq!(sprintf!("[{v[0]}] [1, 2] [x] {v:c}"), "[1] [1, 2] [x] [1, 2]");
+    assert_eq!(sprintf!(r"\[bold] stays"), "[bold] stays");
+    assert!(style::color_enabled(Stream::Stderr));
+}
+
+#[test]
+fn writef_is_styled_with_always() {
+    use std::fmt::Write;
+    style::set_color_choice(ColorChoice::Always);
+    let mut log = String::new();
+    cio::writef!(log, "[red]x[/]").unwrap();
+    assert_eq!(log, "\x1b[31mx\x1b[0m\n");
+    assert!(style::color_enabled(Stream::Writer));
+}
@@ -0,0 +1,130 @@
+use cio::sprintf;
+use cio::table::{format_table, Row};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+#[derive(Row)]
+struct Person {
+    name: String,
+    age: u32,
+    hobbies: Vec<&'static str>,
+}
+
+fn people() -> Vec<Person> {
+    vec![
+        Person { name: "Alice".to_string(), age: 30, hobbies: vec!["chess"] },
+        Person { name: "Bob".to_string(), age: 7, hobbies: vec![] },
+    ]
+}
+
+#[test]
+fn boxed_table_of_records() {
+    let people = people();
+    let expected = "\
+┌───────┬─────┬───────────┐
+│ name  │ age │ hobbies   │
+├───────┼─────┼───────────┤
+│ Alice │  30 │ [\"chess\"] │
+│ Bob   │   7 │ []        │
+└───────┴─────┴───────────┘";
+    assert_eq!(sprintf!("{people:t}"), expected);
+}
+
+#[test]
+fn markdown_table_of_records() {
+    let people = people();
+    let expected = "\
+| name  | age | hobbies   |
+| ----- | --: | --------- |
+| Alice |  30 | [\"chess\"] |
+| Bob   |   7 | []        |";
+    assert_eq!(sprintf!("{people:tm}"), expected);
+}
+
+#[test]
+fn tables_of_sequences_and_tuples() {
+    let matrix = vec![vec![1, 20], vec![300, 4]];
+    assert_eq!(format_table(&matrix, true, false), "|   0 |   1 |\n| --: | --: |\n|   1 |  20 |\n| 300 |   4 |");
+    let pairs = [("x", 1.5), ("yy", -2.0)];
+    assert_eq!(format_table(&pairs, true, false), "| 0   |   1 |\n| --- | --: |\n| x   | 1.5 |\n| yy  |  -2 |");
+}
+
+#[test]
+fn tables_of_maps() {
+    let scores = BTreeMap::from([("ada", 3), ("bo", 12)]);
+    assert_eq!(format_table(&scores, true, false), "| key | value |\n| --- | ----: |\n| ada |     3 |\n| bo  |    12 |");
+    let people = HashMap::from([(1, people().remove(1))]);
+    assert_eq!(format_table(&people, true, false), "| key | name | age | hobbies |\n| --: | ---- | --: | ------- |\n|   1 | Bob  |   7 | []      |");
+}
+
+#[test]
+fn sorted_tables_of_hash_maps() {
+    let stock: HashMap<&str, u32> = HashMap::from([("pear", 4), ("apple", 12), ("fig", 0), ("kiwi", 7)]);
+    assert_eq!(
+        sprintf!("{stock:tm+sorted}"),
+        "| key   | value |\n| ----- | ----: |\n| apple |    12 |\n| fig   |     0 |\n| kiwi  |     7 |\n| pear  |     4 |"
+    );
+    let ranks: HashMap<u16, char> = HashMap::from([(10, 'c'), (9, 'b'), (100, 'd'), (1, 'a')]);
+    let expected = "\
+┌─────┬───────┐
+│ key │ value │
+├─────┼───────┤
+│   1 │ a     │
+│   9 │ b     │
+│  10 │ c     │
+│ 100 │ d     │
+└─────┴───────┘";
+    assert_eq!(sprintf!("{ranks:t+sorted}"), expected);
+    let rows = vec![(2, 'b'), (1, 'a')];
+    assert_eq!(sprintf!("{rows:tm+sorted}"), sprintf!("{rows:tm}"));
+}
+
+#[test]
+fn tables_of_hash_sets() {
+    let points: HashSet<(i32, i32)> = HashSet::from([(10, 1), (9, 5), (9, -2), (-1, 0)]);
+    assert_eq!(
+        sprintf!("{points:tm+sorted}"),
+        "|   0 |   1 |\n| --: | --: |\n|  -1 |   0 |\n|   9 |  -2 |\n|   9 |   5 |\n|  10 |   1 |"
+    );
+    let table = sprintf!("{points:t}");
+    assert_eq!(table.lines().count(), 8);
+    assert!(table.contains("│ 10 │  1 │"));
+}
+
+#[derive(Row)]
+struct Labeled<T> {
+    label: &'static str,
+    y: T,
+}
+
+#[derive(Row)]
+struct Pair<A, B>(A, B);
+
+#[test]
+fn generic_rows_show_their_parameters_with_debug() {
+    let rows = [Labeled { label: "a", y: Some(1) }, Labeled { label: "b", y: None }];
+    assert_eq!(sprintf!("{rows:tm}"), "| label | y       |\n| ----- | ------- |\n| a     | Some(1) |\n| b     | None    |");
+    assert_eq!(sprintf!("{:tm}", [Pair('x', "y")]), "| 0   | 1   |\n| --- | --- |\n| 'x' | \"y\" |");
+}
+
+#[test]
+fn unicode_width_padding() {
+    let cities = vec![("東京", 37), ("Paris", 11)];
+    let expected = "\
+┌───────┬────┐
+│ 0     │  1 │
+├───────┼────┤
+│ 東京  │ 37 │
+│ Paris │ 11 │
+└───────┴────┘";
+    assert_eq!(format_table(&cities, false, false), expected);
+}
+
+#[test]
+fn tuple_structs_and_empty_tables() {
+    #[derive(Row)]
+    struct Point(i32, i32);
+    assert_eq!(format_table(&[Point(1, -2)], true, false), "|   0 |   1 |\n| --: | --: |\n|   1 |  -2 |");
+    assert_eq!(Person::headers(), Some(vec!["name".to_string(), "age".to_string(), "hobbies".to_string()]));
+    let empty: Vec<Person> = Vec::new();
+    assert_eq!(format_table(&empty, true, false), "| name | age | hobbies |\n| ---- | --- | ------- |");
+}
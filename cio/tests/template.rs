@@ -0,0 +1,48 @@
+use cio::{printf_file, sprintf, writef};
+
+mod common;
+use common::{is_child, printed, run, stdout, END, START};
+
+#[test]
+fn include_str_template() {
+    let name = "Q3";
+    let total = 1234567;
+    let items = vec![1, 2, 3];
+    let report = sprintf!(include_str!("templates/report.tmpl"));
+    assert_eq!(report, "Report for Q3\nTotal: 1,234,567\nItems: [1, 2, 3]\n");
+}
+
+#[test]
+fn concat_template() {
+    let x = 1.5;
+    assert_eq!(sprintf!(concat!("x = {x:.2}", ", ", 'y', 2, " {x=}")), "x = 1.50, y2 x=1.5");
+    assert_eq!(sprintf!(concat!("{x}", concat!(" and ", "{x:>5}"))), "1.5 and   1.5");
+}
+
+#[test]
+fn writef_with_template() {
+    use std::fmt::Write;
+    let (name, total, items) = ("Q4", 12, [4]);
+    let mut report = String::new();
+    writef!(report, include_str!("templates/report.tmpl")).unwrap();
+    assert_eq!(report, "Report for Q4\nTotal:     12\nItems: [4]\n\n");
+}
+
+#[test]
+fn child_printf_file() {
+    if is_child() {
+        let (name, total, items) = ("Q1", 0, Vec::<u8>::new());
+        print!("{START}");
+        printf_file!("tests/templates/report.tmpl");
+        print!("{END}");
+    }
+}
+
+#[test]
+fn printf_file_prints_the_template() {
+    let (name, total, items) = ("Q1", 0, Vec::<u8>::new());
+    let expected = sprintf!(include_str!("templates/report.tmpl"));
+    assert_eq!(expected, "Report for Q1\nTotal:      0\nItems: []\n");
+    let output = run("child_printf_file", "");
+    assert_eq!(printed(&stdout(&output)), expected + "\n");
+}
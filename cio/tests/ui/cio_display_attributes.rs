@@ -0,0 +1,14 @@
+use cio::CioDisplay;
+
+#[derive(CioDisplay)]
+struct Report {
+    #[cio(hide)]
+    secret: String,
+    #[cio(spec = ".2k")]
+    total: f64,
+}
+
+#[derive(CioDisplay)]
+struct Pair(i32, i32);
+
+fn main() {}
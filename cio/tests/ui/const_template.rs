@@ -0,0 +1,8 @@
+use cio::printf;
+
+const TEMPLATE: &str = "{x}";
+
+fn main() {
+    let x = 1;
+    printf!(TEMPLATE);
+}
@@ -0,0 +1,5 @@
+use cio::printf;
+
+fn main() {
+    printf!("Nothing here: {}");
+}
@@ -0,0 +1,5 @@
+use cio::input;
+
+fn main() {
+    let rows: Vec<(u8, u8)> = input!("Rows: ");
+}
\ No newline at end of file
@@ -0,0 +1,16 @@
+use cio::{input, try_input};
+
+fn main() {
+    let a: u32 = input!("A: ", retries = 3);
+    let b: Result<u32, _> = try_input!("B: ", attempts = 3);
+    let flag = true;
+    let c: Option<u32> = input!("C: ", allow_empty = flag);
+    let d: Option<u32> = input!("D: ", allow_empty, default = 1);
+}
+
+fn checks() {
+    let e: String = input!("E: ", matches = "[a-z");
+    let pattern = "^[a-z]+$";
+    let f: String = input!("F: ", matches = pattern);
+    let g: u32 = input!("G: ", message = "must be even");
+}
@@ -0,0 +1,6 @@
+use cio::printf;
+
+fn main() {
+    let (v, w) = (vec![1.5], 5);
+    printf!("{v:a<edge={w}>} {v:{w}k} {v:{w + }}");
+}
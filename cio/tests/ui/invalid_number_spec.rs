@@ -0,0 +1,6 @@
+use cio::printf;
+
+fn main() {
+    let n = 255;
+    printf!("{n:,x} {n:.2d} {n:+s} {n:,_}");
+}
@@ -0,0 +1,6 @@
+use cio::printf;
+
+fn main() {
+    let v = vec![1, 2, 3];
+    printf!("{v:a<edge=x>} {v:j<width=3>} {v:a<edge=1, edge=2>}");
+}
@@ -0,0 +1,5 @@
+use cio::printf;
+
+fn main() {
+    printf!("{} and {} and {3}", 1, 2);
+}
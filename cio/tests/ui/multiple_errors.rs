@@ -0,0 +1,6 @@
+use cio::printf;
+
+fn main() {
+    let x = 1;
+    printf!("{x +} and {x:zz} and {x.} }");
+}
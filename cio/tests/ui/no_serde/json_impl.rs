@@ -0,0 +1,13 @@
+use cio::sprintf;
+
+struct Point {
+    x: i32,
+}
+
+impl cio::json::Json for Point {}
+
+fn main() {
+    let point = Point { x: 1 };
+    let _ = point.x;
+    let _ = sprintf!("{point:json}");
+}
\ No newline at end of file
@@ -0,0 +1,6 @@
+use cio::printf;
+
+fn main() {
+    let template = "{x}";
+    printf!(template);
+}
@@ -0,0 +1,7 @@
+use cio::printf;
+
+fn main() {
+    let name = "Alice";
+    let ratio = 0.5;
+    printf!("{name:,} {ratio:_x}");
+}
@@ -0,0 +1,5 @@
+use cio::printf;
+
+fn main() {
+    printf!("{a} {}", a = 1, 2);
+}
@@ -0,0 +1,10 @@
+use cio::printf;
+
+fn main() {
+    let x = 3.14159;
+    let width = 8;
+    printf!("{x:.*}");
+    printf!("{x:1$}");
+    printf!("{x:.1$}");
+    printf!("{x:width$.2}");
+}
\ No newline at end of file
@@ -0,0 +1,7 @@
+use cio::print_py;
+
+fn main() {
+    let sep = ", ";
+    print_py!("a", "b", sep = sep);
+    print_py!("a", "b", file = std::io::stderr());
+}
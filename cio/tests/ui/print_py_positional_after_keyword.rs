@@ -0,0 +1,6 @@
+use cio::print_py;
+
+fn main() {
+    let x = 1;
+    print_py!("x:", end = "", x);
+}
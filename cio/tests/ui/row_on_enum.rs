@@ -0,0 +1,8 @@
+use cio::table::Row;
+
+#[derive(Row)]
+enum Shape {
+    Circle,
+}
+
+fn main() {}
@@ -0,0 +1,6 @@
+use cio::printf;
+
+fn main() {
+    let v = vec![1, 2];
+    printf!("{v!r:a} {v!s:j} {v!a:>10}");
+}
@@ -0,0 +1,9 @@
+use cio::printf;
+
+struct Opaque;
+
+fn main() {
+    let rows = vec![Opaque];
+    let n = 3;
+    printf!("{rows:t} {n:tm}");
+}
@@ -0,0 +1,7 @@
+use cio::printf;
+
+fn main() {
+    let name = "Q3";
+    let total = 10;
+    printf!(include_str!("templates/bad.tmpl"));
+}
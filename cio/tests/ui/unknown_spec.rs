@@ -0,0 +1,6 @@
+use cio::printf;
+
+fn main() {
+    let pi = 3.14;
+    printf!("PI: {pi:.2z}");
+}
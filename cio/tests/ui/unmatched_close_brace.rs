@@ -0,0 +1,6 @@
+use cio::printf;
+
+fn main() {
+    let age = 30;
+    printf!("Age: {age}}");
+}
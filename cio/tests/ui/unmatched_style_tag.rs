@@ -0,0 +1,6 @@
+use cio::printf;
+
+fn main() {
+    let n = 1;
+    printf!("[bold]{n}[/red] and [/]");
+}
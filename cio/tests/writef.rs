@@ -0,0 +1,40 @@
+use cio::{eprintf, writef};
+
+mod common;
+use common::{is_child, run, stderr, stdout};
+
+#[test]
+fn writef_to_fmt_write() {
+    use std::fmt::Write;
+    let mut report = String::new();
+    let total = 42;
+    writef!(report, "Total: {total:>4}").unwrap();
+    writef!(&mut report, "Items: {vec![1, 2]:c}").unwrap();
+    assert_eq!(report, "Total:   42\nItems: [1, 2]\n");
+}
+
+#[test]
+fn writef_to_io_write() -> std::io::Result<()> {
+    use std::io::Write;
+    let mut buffer = Vec::new();
+    let name = "cio";
+    writef!(buffer, "Hello from {name.to_uppercase()}")?;
+    assert_eq!(buffer, b"Hello from CIO\n");
+    Ok(())
+}
+
+#[test]
+fn child_eprintf() {
+    if is_child() {
+        let code = 2;
+        eprintf!("warning: exit code {code}");
+        eprintf!("[{code:>3}]");
+    }
+}
+
+#[test]
+fn eprintf_prints_lines_to_stderr() {
+    let output = run("child_eprintf", "");
+    assert_eq!(stderr(&output), "warning: exit code 2\n[  2]\n");
+    assert!(!stdout(&output).contains("warning"));
+}
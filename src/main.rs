@@ -1,6 +1,8 @@
 use cio::{printf, input};
+use cio::table::Row;
 use std::collections::{HashMap, BTreeMap, HashSet, BTreeSet, VecDeque, LinkedList, BinaryHeap};
 use std::cmp::Reverse;
+use serde::Serialize;
 
 fn main() {
     // Program title
@@ -26,6 +28,10 @@ fn main() {
     printf!("First letter of the last name: {last_name.chars().next().unwrap()}");
     printf!("Is your favorite letter uppercase? {favorite_letter.is_uppercase()}");
     printf!("Your favorite letter ASCII value: {favorite_letter as u8}");
+    printf!("Self-documenting: {age=}, {last_name.len() = }, {height * 100.0 = :.0}");
+    printf!("Conversion flags: {last_name!r} {last_name!a:>12} {married!s}");
+    printf!("Extra arguments: {} turns 100 in {years} years", first_name, years = 100 - age);
+    printf!("[bold green]Styled output:[/] {age:cyan} years, [italic]{last_name}[/]");
     printf!("------------------------------------------------");
 
     // 3. Number formatting
@@ -38,6 +44,11 @@ fn main() {
     printf!("Integer as hexadecimal: {age:x}");
     printf!("Integer as binary: {age:b}");
     printf!("Float with scientific notation: {pi:e}");
+    printf!("Thousands separators: {1234567.891:,.2f} and {299792458:_}");
+    printf!("Percentage: {age as f64 / 100.0:.1%}");
+    printf!("Sign-aware padding: [{-age:=+8}] [{age: }]");
+    let (width, precision) = (12, 3);
+    printf!("Dynamic width and precision: [{pi:>{width}.{precision}}] [{last_name:-^{width}}]");
     printf!("------------------------------------------------");
 
     // 4. Array-type containers (sequence containers)
@@ -81,6 +92,8 @@ fn main() {
 
     printf!("2D Matrix (:a):\n{matrix_2d:a}");
     printf!("2D Matrix (:c): {matrix_2d:c}");
+    printf!("2D Matrix (:m):\n{matrix_2d:m}");
+    printf!("2D Matrix with indexes (:mi):\n{matrix_2d:mi}");
     printf!("3D Matrix (:a):\n{matrix_3d:a}");
     printf!("3D Matrix (:c): {matrix_3d:c}");
     printf!("4D Matrix (:a):\n{matrix_4d:a}");
@@ -133,6 +146,11 @@ fn main() {
     printf!("HashSet: {hash_set:c}");
     printf!("BTreeSet: {btree_set:c}");
 
+    // Deterministic order for hash containers with the +sorted modifier
+    printf!("\nSorted display (:c+sorted and :j+sorted):");
+    printf!("HashMap: {hash_map:c+sorted}");
+    printf!("HashSet:\n{hash_set:j+sorted}");
+
     // Map operations
     printf!("\nMap operations:");
     printf!("Capital of France: {hash_map.get(\"France\").unwrap()}");
@@ -238,7 +256,7 @@ fn main() {
     printf!("Flattened and filtered (odd numbers squared): {flattened_filtered:a}");
 
     // Turbofish with custom data types
-    #[derive(Debug)]
+    #[derive(Debug, Serialize, Row)]
     struct Person {
         name: String,
         age: i32,
@@ -288,6 +306,7 @@ fn main() {
         .join(", ");                        // Join with commas
 
     printf!("Names sorted by age: {names}");
+    printf!("People as a table (:t):\n{people:t}");
 
     // Manipulate data using reference to self
     let mut alice = Person::new("Alice", 30);
@@ -313,6 +332,8 @@ fn main() {
         .collect();
 
     printf!("\nName to age mapping (:j):\n{name_to_age:j}");
+    printf!("Name to age mapping (:json): {name_to_age:json}");
+    printf!("\nPeople as JSON (:json#):\n{people:json#}");
 
     // Enums in collections
     #[derive(Debug)]
@@ -353,6 +374,9 @@ fn main() {
     printf!("- Format :a: Best for 1D arrays and matrices (adds indentation to matrices)");
     printf!("- Format :j: Best for maps and complex structures (pretty-printed)");
     printf!("- Format :c: Best for compact display (single-line for simple structures)");
+    printf!("- Format :m: Best for 2D numeric matrices (right-aligned columns)");
+    printf!("- Format :json/:json#: Valid JSON for Serialize types (compact/indented)");
+    printf!("- Format :t/:tm: Tables of records, tuples or maps (boxed/Markdown)");
 
     // Conclusion
     printf!("\n=== End of the demonstration ===");